@@ -0,0 +1,47 @@
+#import bevy_pbr::mesh_types
+#import bevy_pbr::mesh_view_bindings
+
+@group(1) @binding(0)
+var<uniform> mesh: Mesh;
+
+// NOTE: Bindings must come before functions that use them!
+#import bevy_pbr::mesh_functions
+
+struct Vertex {
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+    @location(2) uv: vec2<f32>,
+
+    @location(7) i_pos_scale: vec4<f32>,
+    @location(8) i_rotation: vec4<f32>,
+    @location(9) i_color: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) world_normal: vec3<f32>,
+    @location(1) color: vec4<f32>,
+};
+
+// Rotate v by the quaternion q
+fn rotate(q: vec4<f32>, v: vec3<f32>) -> vec3<f32> {
+    return v + 2.0 * cross(q.xyz, cross(q.xyz, v) + q.w * v);
+}
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    let position = rotate(vertex.i_rotation, vertex.position * vertex.i_pos_scale.w) + vertex.i_pos_scale.xyz;
+    var out: VertexOutput;
+    out.clip_position = mesh_position_local_to_clip(mesh.model, vec4<f32>(position, 1.0));
+    out.world_normal = rotate(vertex.i_rotation, vertex.normal);
+    out.color = vertex.i_color;
+    return out;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Cheap fixed sun plus ambient so the faces of the cubes can be told apart
+    let sun = normalize(vec3<f32>(1.0, 1.0, 1.0));
+    let light = max(dot(normalize(in.world_normal), sun), 0.0) * 0.8 + 0.2;
+    return vec4<f32>(in.color.rgb * light, in.color.a);
+}
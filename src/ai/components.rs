@@ -0,0 +1,58 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use super::tree::BehaviourTree;
+
+/// Runs a behaviour tree asset for this entity every AI tick.
+#[derive(Component)]
+pub struct BehaviourTreeRunner {
+    pub tree: Handle<BehaviourTree>,
+    pub memory: Vec<NodeMemory>,
+    pub elapsed: f32,
+    pub ticks: u32,
+}
+
+impl BehaviourTreeRunner {
+    pub fn new(tree: Handle<BehaviourTree>) -> Self {
+        BehaviourTreeRunner {
+            tree,
+            memory: vec![],
+            elapsed: 0.0,
+            ticks: 0,
+        }
+    }
+}
+
+/// Per-node state that has to survive between ticks (which child is running, timers, counters).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NodeMemory {
+    pub running_child: usize,
+    pub count: u32,
+    pub timer: Option<f32>,
+    pub last_tick: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BlackboardValue {
+    Bool(bool),
+    Float(f32),
+    Position(Vec3),
+    Entity(Entity),
+}
+
+/// Scratch memory shared between the nodes of a unit's behaviour tree.
+#[derive(Component, Default, Debug)]
+pub struct Blackboard(pub HashMap<String, BlackboardValue>);
+
+impl Blackboard {
+    pub fn get(&self, key: &str) -> Option<BlackboardValue> {
+        self.0.get(key).copied()
+    }
+
+    pub fn set(&mut self, key: &str, value: BlackboardValue) {
+        self.0.insert(key.to_string(), value);
+    }
+
+    pub fn remove(&mut self, key: &str) {
+        self.0.remove(key);
+    }
+}
@@ -0,0 +1,27 @@
+use bevy::prelude::*;
+
+use crate::simulation::SimulationSet;
+
+pub mod components;
+mod systems;
+pub mod tree;
+
+use systems::*;
+use tree::*;
+
+/// The behaviour tree every enemy runs.
+pub const ENEMY_BEHAVIOUR_PATH: &str = "ai/enemy.bt.ron";
+
+pub struct AiPlugin;
+
+impl Plugin for AiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<BehaviourTree>()
+            .init_asset_loader::<BehaviourTreeLoader>()
+            .add_system(
+                tick_behaviour_trees
+                    .in_set(SimulationSet::Ai)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            );
+    }
+}
@@ -0,0 +1,378 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use bevy_turborand::prelude::*;
+
+use crate::{
+    combat::components::{AttackTarget, Health, UnitAttack},
+    simulation::resources::SimulationClock,
+    units::components::{Enemy, Player, UnitSize, UnitView, WalkToLocation},
+    vision::fov::scan_fov,
+    world_gen::resources::Ground,
+};
+
+use super::{components::*, tree::*};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    Success,
+    Failure,
+    Running,
+}
+
+/// Everything a node needs to know about the unit it's running for, and the world around it.
+struct TickContext<'a, 'w, 's> {
+    entity: Entity,
+    transform: &'a Transform,
+    size: &'a UnitSize,
+    view: &'a UnitView,
+    health: Option<&'a Health>,
+    can_attack: bool,
+    attacking: Option<Entity>,
+    blackboard: &'a mut Blackboard,
+    commands: &'a mut Commands<'w, 's>,
+    rapier_context: &'a RapierContext,
+    rng: &'a mut GlobalRng,
+    bounds: f32,
+    position_of: &'a dyn Fn(Entity) -> Option<Vec3>,
+    is_hostile: &'a dyn Fn(Entity) -> bool,
+    elapsed: f32,
+    tick: u32,
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn tick_behaviour_trees(
+    mut commands: Commands,
+    clock: Res<SimulationClock>,
+    trees: Res<Assets<BehaviourTree>>,
+    rapier_context: Res<RapierContext>,
+    ground: Res<Ground>,
+    mut rng: ResMut<GlobalRng>,
+    mut runners: Query<(
+        Entity,
+        &mut BehaviourTreeRunner,
+        &mut Blackboard,
+        &Transform,
+        &UnitSize,
+        &UnitView,
+        Option<&Health>,
+        Option<&UnitAttack>,
+        Option<&AttackTarget>,
+    )>,
+    transforms: Query<&Transform>,
+    teams: Query<(Option<&Player>, Option<&Enemy>)>,
+) {
+    let dt = clock.delta_seconds();
+    let position_of = |e: Entity| transforms.get(e).ok().map(|t| t.translation);
+    let bounds = (ground.size / 2) as f32 - 1.0;
+
+    for (entity, mut runner, mut blackboard, transform, size, view, health, attack, attacking) in
+        runners.iter_mut()
+    {
+        // Tree might not have finished loading yet.
+        let Some(tree) = trees.get(&runner.tree) else { continue; };
+        if tree.nodes.is_empty() {
+            continue;
+        }
+
+        // Asset was (re)loaded with a different shape so our old state means nothing now.
+        if runner.memory.len() != tree.nodes.len() {
+            runner.memory = vec![NodeMemory::default(); tree.nodes.len()];
+        }
+
+        runner.elapsed += dt;
+
+        let is_hostile = |other: Entity| {
+            matches!(
+                (teams.get(entity), teams.get(other)),
+                (Ok((_, Some(_))), Ok((Some(_), _))) | (Ok((Some(_), _)), Ok((_, Some(_))))
+            )
+        };
+
+        runner.ticks += 1;
+        let runner = runner.as_mut();
+        let mut ctx = TickContext {
+            entity,
+            transform,
+            size,
+            view,
+            health,
+            can_attack: attack.is_some(),
+            attacking: attacking.map(|a| a.0),
+            blackboard: blackboard.as_mut(),
+            commands: &mut commands,
+            rapier_context: &rapier_context,
+            rng: rng.as_mut(),
+            bounds,
+            position_of: &position_of,
+            is_hostile: &is_hostile,
+            elapsed: runner.elapsed,
+            tick: runner.ticks,
+        };
+
+        tick_node(tree, 0, &mut runner.memory, &mut ctx);
+    }
+}
+
+fn tick_node(
+    tree: &BehaviourTree,
+    index: usize,
+    memory: &mut [NodeMemory],
+    ctx: &mut TickContext,
+) -> Status {
+    let node = &tree.nodes[index];
+
+    // A node that wasn't ticked last time was interrupted, so it starts over rather than
+    // picking up a half finished wait or sequence from ages ago. Cooldowns are the exception,
+    // they have to outlast being skipped over or they'd never cool down at all.
+    let interrupted = memory[index].last_tick + 1 < ctx.tick;
+    if interrupted && !matches!(node.kind, NodeKind::Cooldown { .. }) {
+        memory[index] = NodeMemory::default();
+    }
+    memory[index].last_tick = ctx.tick;
+
+    match &node.kind {
+        NodeKind::Sequence => {
+            let start = memory[index].running_child;
+            for (i, &child) in node.children.iter().enumerate().skip(start) {
+                match tick_node(tree, child, memory, ctx) {
+                    Status::Success => continue,
+                    Status::Running => {
+                        memory[index].running_child = i;
+                        return Status::Running;
+                    }
+                    Status::Failure => {
+                        memory[index].running_child = 0;
+                        return Status::Failure;
+                    }
+                }
+            }
+            memory[index].running_child = 0;
+            Status::Success
+        }
+        NodeKind::Selector => {
+            let start = memory[index].running_child;
+            for (i, &child) in node.children.iter().enumerate().skip(start) {
+                match tick_node(tree, child, memory, ctx) {
+                    Status::Failure => continue,
+                    Status::Running => {
+                        memory[index].running_child = i;
+                        return Status::Running;
+                    }
+                    Status::Success => {
+                        memory[index].running_child = 0;
+                        return Status::Success;
+                    }
+                }
+            }
+            memory[index].running_child = 0;
+            Status::Failure
+        }
+        NodeKind::ReactiveSelector => {
+            let previous = memory[index].running_child;
+            for (i, &child) in node.children.iter().enumerate() {
+                let status = tick_node(tree, child, memory, ctx);
+                if status == Status::Failure {
+                    continue;
+                }
+
+                // Something more important took over, whatever was running has to start over
+                if i < previous {
+                    reset_subtree(tree, node.children[previous], memory);
+                }
+                memory[index].running_child = if status == Status::Running { i } else { 0 };
+                return status;
+            }
+            memory[index].running_child = 0;
+            Status::Failure
+        }
+        NodeKind::Parallel { success_threshold } => {
+            let mut successes = 0;
+            let mut failures = 0;
+            for &child in node.children.iter() {
+                match tick_node(tree, child, memory, ctx) {
+                    Status::Success => successes += 1,
+                    Status::Failure => failures += 1,
+                    Status::Running => {}
+                }
+            }
+
+            if successes >= *success_threshold {
+                Status::Success
+            } else if failures > node.children.len().saturating_sub(*success_threshold) {
+                Status::Failure
+            } else {
+                Status::Running
+            }
+        }
+        NodeKind::Inverter => match tick_node(tree, node.children[0], memory, ctx) {
+            Status::Success => Status::Failure,
+            Status::Failure => Status::Success,
+            Status::Running => Status::Running,
+        },
+        NodeKind::Succeeder => match tick_node(tree, node.children[0], memory, ctx) {
+            Status::Running => Status::Running,
+            _ => Status::Success,
+        },
+        NodeKind::Repeat { times } => match tick_node(tree, node.children[0], memory, ctx) {
+            Status::Running => Status::Running,
+            Status::Failure => {
+                memory[index].count = 0;
+                Status::Failure
+            }
+            Status::Success => {
+                memory[index].count += 1;
+                match times {
+                    Some(times) if memory[index].count >= *times => {
+                        memory[index].count = 0;
+                        Status::Success
+                    }
+                    _ => Status::Running,
+                }
+            }
+        },
+        NodeKind::Cooldown { seconds } => {
+            if let Some(ready_at) = memory[index].timer {
+                if ctx.elapsed < ready_at {
+                    return Status::Failure;
+                }
+            }
+
+            let status = tick_node(tree, node.children[0], memory, ctx);
+            if status == Status::Success {
+                memory[index].timer = Some(ctx.elapsed + seconds);
+            }
+            status
+        }
+        NodeKind::Condition(condition) => check_condition(condition, ctx),
+        NodeKind::Action(action) => run_action(action, &mut memory[index], ctx),
+    }
+}
+
+/// Forget how far a subtree had got. Cooldowns keep their timers, same as when interrupted.
+fn reset_subtree(tree: &BehaviourTree, index: usize, memory: &mut [NodeMemory]) {
+    let node = &tree.nodes[index];
+    if !matches!(node.kind, NodeKind::Cooldown { .. }) {
+        memory[index] = NodeMemory::default();
+    }
+    for &child in node.children.iter() {
+        reset_subtree(tree, child, memory);
+    }
+}
+
+/// Resolve a blackboard key to a world position, entities are looked up where they are right now.
+fn position_from_key(key: &str, ctx: &TickContext) -> Option<Vec3> {
+    match ctx.blackboard.get(key)? {
+        BlackboardValue::Position(position) => Some(position),
+        BlackboardValue::Entity(entity) => (ctx.position_of)(entity),
+        _ => None,
+    }
+}
+
+fn check_condition(condition: &BehaviourCondition, ctx: &mut TickContext) -> Status {
+    let passed = match condition {
+        BehaviourCondition::Has(key) => ctx.blackboard.get(key).is_some(),
+        BehaviourCondition::InRange { key, distance } => position_from_key(key, ctx)
+            .map(|position| ctx.transform.translation.distance(position) <= *distance)
+            .unwrap_or(false),
+        BehaviourCondition::HealthBelow(fraction) => ctx
+            .health
+            .map(|health| health.current / health.max < *fraction)
+            .unwrap_or(false),
+    };
+
+    if passed {
+        Status::Success
+    } else {
+        Status::Failure
+    }
+}
+
+fn run_action(action: &BehaviourAction, memory: &mut NodeMemory, ctx: &mut TickContext) -> Status {
+    match action {
+        BehaviourAction::Scan { store_as } => {
+            let origin =
+                ctx.transform.translation + (ctx.transform.forward() * ctx.size.collider / 2.0);
+            let sensitivity = 10;
+            let seen = scan_fov(
+                ctx.rapier_context,
+                ctx.entity,
+                ctx.transform,
+                ctx.view.fov,
+                origin,
+                sensitivity,
+                ctx.view.distance,
+            )
+            .iter()
+            .filter_map(|result| result.entity)
+            .find(|entity| *entity != ctx.entity && (ctx.is_hostile)(*entity));
+
+            if let Some(seen) = seen {
+                ctx.blackboard.set(store_as, BlackboardValue::Entity(seen));
+                Status::Success
+            } else {
+                ctx.blackboard.remove(store_as);
+                Status::Failure
+            }
+        }
+        BehaviourAction::MoveTo { key, tolerance } => {
+            let Some(target) = position_from_key(key, ctx) else {
+                ctx.blackboard.remove(key);
+                return Status::Failure;
+            };
+
+            let here = ctx.transform.translation;
+            if Vec2::new(here.x, here.z).distance(Vec2::new(target.x, target.z)) <= *tolerance {
+                ctx.commands.entity(ctx.entity).remove::<WalkToLocation>();
+                return Status::Success;
+            }
+
+            // Keep our own height, we only care about getting there on the ground.
+            ctx.commands
+                .entity(ctx.entity)
+                .insert(WalkToLocation(Vec3::new(target.x, here.y, target.z)));
+            Status::Running
+        }
+        BehaviourAction::Attack { key } => {
+            if !ctx.can_attack {
+                return Status::Failure;
+            }
+            let Some(BlackboardValue::Entity(target)) = ctx.blackboard.get(key) else { return Status::Failure; };
+
+            if (ctx.position_of)(target).is_none() {
+                // It's gone, if we were the ones fighting it call that a win.
+                ctx.blackboard.remove(key);
+                return if ctx.attacking == Some(target) {
+                    Status::Success
+                } else {
+                    Status::Failure
+                };
+            }
+
+            if ctx.attacking != Some(target) {
+                ctx.commands.entity(ctx.entity).insert(AttackTarget(target));
+            }
+            Status::Running
+        }
+        BehaviourAction::Wander { radius, store_as } => {
+            let offset = Vec3::new(ctx.rng.f32_normalized(), 0.0, ctx.rng.f32_normalized());
+            let target = (ctx.transform.translation + offset * *radius)
+                .clamp(Vec3::splat(-ctx.bounds), Vec3::splat(ctx.bounds));
+            ctx.blackboard
+                .set(store_as, BlackboardValue::Position(target));
+            Status::Success
+        }
+        BehaviourAction::Wait(seconds) => {
+            let done_at = *memory.timer.get_or_insert(ctx.elapsed + seconds);
+            if ctx.elapsed >= done_at {
+                memory.timer = None;
+                Status::Success
+            } else {
+                Status::Running
+            }
+        }
+        BehaviourAction::Clear(key) => {
+            ctx.blackboard.remove(key);
+            Status::Success
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use serde::{Deserialize, Serialize};
+
+/// The shape of a behaviour tree as it's written in a `.bt.ron` asset file.
+///
+/// ```ron
+/// ReactiveSelector([
+///     Sequence([Action(Scan(store_as: "target")), Action(Attack(key: "target"))]),
+///     Sequence([Action(Wander(radius: 5.0, store_as: "wander")), Action(MoveTo(key: "wander", tolerance: 0.5))]),
+/// ])
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BehaviourNode {
+    /// Runs children in order until one fails.
+    Sequence(Vec<BehaviourNode>),
+    /// Runs children in order until one succeeds, carrying on from a running child next tick.
+    Selector(Vec<BehaviourNode>),
+    /// Like `Selector` but starts from the first child every tick, so a higher priority child can
+    /// take over from one that's still running (which then starts over next time).
+    ReactiveSelector(Vec<BehaviourNode>),
+    /// Ticks every child each tick, succeeds once `success_threshold` of them have succeeded.
+    Parallel {
+        success_threshold: usize,
+        children: Vec<BehaviourNode>,
+    },
+    Inverter(Box<BehaviourNode>),
+    /// Always succeeds once the child has finished, handy for optional branches.
+    Succeeder(Box<BehaviourNode>),
+    /// Repeat the child `times` times, or forever if `None`.
+    Repeat {
+        times: Option<u32>,
+        child: Box<BehaviourNode>,
+    },
+    /// Fails without ticking the child until `seconds` have passed since it last succeeded.
+    Cooldown {
+        seconds: f32,
+        child: Box<BehaviourNode>,
+    },
+    Condition(BehaviourCondition),
+    Action(BehaviourAction),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BehaviourCondition {
+    /// Blackboard has a value under the key.
+    Has(String),
+    /// The position/entity under the key is within distance of us.
+    InRange { key: String, distance: f32 },
+    /// Our health is below this fraction (0.0 - 1.0) of max.
+    HealthBelow(f32),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BehaviourAction {
+    /// Look around with `scan_fov` and store the first hostile we can see.
+    Scan { store_as: String },
+    /// Walk to the position/entity under the key.
+    MoveTo { key: String, tolerance: f32 },
+    /// Attack the entity under the key until it or we are dead.
+    Attack { key: String },
+    /// Pick a random spot around us and store it.
+    Wander { radius: f32, store_as: String },
+    /// Do nothing for a while.
+    Wait(f32),
+    /// Remove a key from the blackboard.
+    Clear(String),
+}
+
+/// Node in the flattened tree, children are indexes into `BehaviourTree::nodes`.
+#[derive(Debug, Clone)]
+pub struct Node {
+    pub kind: NodeKind,
+    pub children: Vec<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub enum NodeKind {
+    Sequence,
+    Selector,
+    ReactiveSelector,
+    Parallel { success_threshold: usize },
+    Inverter,
+    Succeeder,
+    Repeat { times: Option<u32> },
+    Cooldown { seconds: f32 },
+    Condition(BehaviourCondition),
+    Action(BehaviourAction),
+}
+
+/// A loaded behaviour tree, flattened so every node has an index we can keep per-unit state against.
+/// The root is always `nodes[0]`.
+#[derive(Debug, Clone, TypeUuid)]
+#[uuid = "5f0e4a1c-3b7e-4a5e-9d0b-8c3f2e6a71d4"]
+pub struct BehaviourTree {
+    pub nodes: Vec<Node>,
+}
+
+impl From<BehaviourNode> for BehaviourTree {
+    fn from(root: BehaviourNode) -> Self {
+        let mut nodes = vec![];
+        flatten(root, &mut nodes);
+        BehaviourTree { nodes }
+    }
+}
+
+fn flatten(node: BehaviourNode, nodes: &mut Vec<Node>) -> usize {
+    let index = nodes.len();
+    let (kind, children) = match node {
+        BehaviourNode::Sequence(children) => (NodeKind::Sequence, children),
+        BehaviourNode::Selector(children) => (NodeKind::Selector, children),
+        BehaviourNode::ReactiveSelector(children) => (NodeKind::ReactiveSelector, children),
+        BehaviourNode::Parallel {
+            success_threshold,
+            children,
+        } => (NodeKind::Parallel { success_threshold }, children),
+        BehaviourNode::Inverter(child) => (NodeKind::Inverter, vec![*child]),
+        BehaviourNode::Succeeder(child) => (NodeKind::Succeeder, vec![*child]),
+        BehaviourNode::Repeat { times, child } => (NodeKind::Repeat { times }, vec![*child]),
+        BehaviourNode::Cooldown { seconds, child } => {
+            (NodeKind::Cooldown { seconds }, vec![*child])
+        }
+        BehaviourNode::Condition(condition) => (NodeKind::Condition(condition), vec![]),
+        BehaviourNode::Action(action) => (NodeKind::Action(action), vec![]),
+    };
+
+    // Reserve our slot first so the parent always comes before its children.
+    nodes.push(Node {
+        kind,
+        children: vec![],
+    });
+    let children = children
+        .into_iter()
+        .map(|child| flatten(child, nodes))
+        .collect();
+    nodes[index].children = children;
+
+    index
+}
+
+#[derive(Default)]
+pub struct BehaviourTreeLoader;
+
+impl AssetLoader for BehaviourTreeLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let root = ron::de::from_bytes::<BehaviourNode>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(BehaviourTree::from(root)));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["bt.ron"]
+    }
+}
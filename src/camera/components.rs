@@ -1,4 +1,73 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 #[derive(Component)]
 pub struct PlayerCamera;
+
+/// Everything needed to put a camera somewhere, as an orbit around a point on the ground.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OrbitPose {
+    pub focus: Vec3,
+    pub radius: f32,
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+impl OrbitPose {
+    /// Blend between two poses, yaw goes the short way around.
+    pub fn lerp(&self, other: &OrbitPose, t: f32) -> OrbitPose {
+        let yaw_delta = (other.yaw - self.yaw + std::f32::consts::PI)
+            .rem_euclid(std::f32::consts::TAU)
+            - std::f32::consts::PI;
+
+        OrbitPose {
+            focus: self.focus.lerp(other.focus, t),
+            radius: self.radius + (other.radius - self.radius) * t,
+            yaw: self.yaw + yaw_delta * t,
+            pitch: self.pitch + (other.pitch - self.pitch) * t,
+        }
+    }
+
+    pub fn rotation(&self) -> Quat {
+        Quat::from_euler(EulerRot::YXZ, self.yaw, self.pitch, 0.0)
+    }
+
+    /// Place the camera transform on the orbit described by focus, radius, yaw and pitch.
+    pub fn apply_to(&self, transform: &mut Transform) {
+        transform.rotation = self.rotation();
+        transform.translation = self.focus + transform.rotation * Vec3::new(0.0, 0.0, self.radius);
+    }
+}
+
+/// What the camera is actually showing, it chases the `PanOrbitCamera` target so pans and zooms
+/// ease in and out instead of snapping.
+#[derive(Component)]
+pub struct SmoothedOrbit(pub OrbitPose);
+
+/// Eases the camera from one pose to another, any camera input cancels it.
+#[derive(Component)]
+pub struct CameraTransition {
+    pub from: OrbitPose,
+    pub to: OrbitPose,
+    pub elapsed: f32,
+    pub duration: f32,
+}
+
+/// Keep the camera focused on this entity until the player pans away.
+#[derive(Component)]
+pub struct CameraFollow(pub Entity);
+
+/// Tactical view straight down with an orthographic projection, north is always up.
+#[derive(Component)]
+pub struct TopDownCamera {
+    pub focus: Vec3,
+    /// Half of how much ground fits vertically on screen, the orthographic version of radius.
+    pub half_height: f32,
+}
+
+/// Debug camera, fly anywhere with the pan keys and look around with the orbit button.
+#[derive(Component)]
+pub struct FreeFlyCamera {
+    pub yaw: f32,
+    pub pitch: f32,
+}
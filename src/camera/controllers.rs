@@ -0,0 +1,390 @@
+use bevy::{prelude::*, render::camera::ScalingMode, window::PrimaryWindow};
+
+use super::{components::*, resources::*, systems::PanOrbitCamera};
+
+/// How high above the ground the top-down camera sits, it's orthographic so this only needs to be
+/// above everything.
+pub const TOP_DOWN_HEIGHT: f32 = 200.0;
+
+/// Everything a camera rig gets to work with each frame, already inverted and scaled by the settings.
+#[derive(Default)]
+pub struct CameraInput {
+    /// Mouse movement (pixels) while the orbit/look button is dragged.
+    pub orbit: Vec2,
+    /// Mouse movement (pixels) while the pan button is dragged.
+    pub pan: Vec2,
+    /// Scroll in lines, positive is zooming in.
+    pub scroll: f32,
+    /// Pan keys, x is right and y is forward.
+    pub keys: Vec2,
+    /// Pushing the cursor against the window edge, same axes as `keys`.
+    pub edge: Vec2,
+    /// Fly up/down keys, only the free-fly rig uses these.
+    pub vertical: f32,
+    pub window: Vec2,
+    /// What's under the cursor, only looked up when zooming to the cursor.
+    pub cursor_hit: Option<Vec3>,
+    pub delta_seconds: f32,
+}
+
+/// What a rig did with the frame's input.
+#[derive(Default)]
+pub struct ControllerResponse {
+    /// Moved at all, cancels any transition that's playing.
+    pub moved: bool,
+    /// Moved the focus, which stops following a unit.
+    pub panned: bool,
+}
+
+/// A way of flying the player's camera around, the active one can be swapped at runtime with
+/// `CameraInputMap::switch_rig`.
+pub trait CameraController: Component + Sized {
+    /// Start a new rig looking at roughly the same spot the previous one was.
+    fn from_view(focus: Vec3, transform: &Transform) -> Self;
+
+    fn projection(&self) -> Projection;
+
+    /// The point on the ground the rig is looking at.
+    fn focus(&self, transform: &Transform) -> Vec3;
+
+    /// Where the rig is as an orbit around a point, for bookmarks, jumps and transitions.
+    fn pose(&self, transform: &Transform) -> OrbitPose;
+
+    /// Put the rig somewhere described by `pose`. Rigs that can't do all of it (the top-down
+    /// camera can't turn or tilt) take what they can and say what they ignore in their own docs.
+    fn set_pose(&mut self, pose: OrbitPose, transform: &mut Transform);
+
+    /// Look at `focus` from the same angle and distance, used for following units.
+    fn set_focus(&mut self, focus: Vec3, transform: &mut Transform) {
+        let mut pose = self.pose(transform);
+        pose.focus = focus;
+        self.set_pose(pose, transform);
+    }
+
+    /// Keep a pose within the zoom and pitch limits from the settings.
+    fn within_limits(pose: OrbitPose, settings: &CameraSettings) -> OrbitPose {
+        OrbitPose {
+            radius: pose.radius.clamp(settings.min_radius, settings.max_radius),
+            pitch: pose.pitch.clamp(settings.min_pitch, settings.max_pitch),
+            ..pose
+        }
+    }
+
+    /// Called every frame, even with no input, so rigs that own their transform can keep it up to date.
+    fn apply_input(
+        &mut self,
+        input: &CameraInput,
+        settings: &CameraSettings,
+        transform: &mut Transform,
+        projection: &mut Projection,
+    ) -> ControllerResponse;
+}
+
+/// How far the ground moves per pixel of mouse movement, so dragged ground stays under the cursor.
+/// Perspective depends on how far away the ground is, orthographic doesn't.
+pub fn world_per_pixel(projection: &Projection, window: Vec2, distance: f32) -> Vec2 {
+    match projection {
+        Projection::Perspective(projection) => {
+            Vec2::new(projection.fov * projection.aspect_ratio, projection.fov) / window * distance
+        }
+        Projection::Orthographic(projection) => projection.area.size() / window,
+    }
+}
+
+pub fn ground_projected(direction: Vec3) -> Vec3 {
+    Vec3::new(direction.x, 0.0, direction.z).normalize_or_zero()
+}
+
+pub fn get_primary_window_size(primary_window: &Query<&Window, With<PrimaryWindow>>) -> Vec2 {
+    let window = primary_window.single();
+    Vec2::new(window.width(), window.height())
+}
+
+impl CameraController for PanOrbitCamera {
+    fn from_view(focus: Vec3, transform: &Transform) -> Self {
+        let (yaw, pitch, _) = transform.rotation.to_euler(EulerRot::YXZ);
+        PanOrbitCamera {
+            focus,
+            radius: transform.translation.distance(focus),
+            yaw,
+            pitch,
+        }
+    }
+
+    fn projection(&self) -> Projection {
+        Projection::Perspective(default())
+    }
+
+    fn focus(&self, _transform: &Transform) -> Vec3 {
+        self.focus
+    }
+
+    fn pose(&self, _transform: &Transform) -> OrbitPose {
+        PanOrbitCamera::pose(self)
+    }
+
+    /// Only the target moves, `update_camera_transform` eases the transform after it.
+    fn set_pose(&mut self, pose: OrbitPose, _transform: &mut Transform) {
+        PanOrbitCamera::set_pose(self, pose);
+    }
+
+    fn apply_input(
+        &mut self,
+        input: &CameraInput,
+        settings: &CameraSettings,
+        transform: &mut Transform,
+        projection: &mut Projection,
+    ) -> ControllerResponse {
+        let mut response = ControllerResponse::default();
+
+        if input.orbit.length_squared() > 0.0 {
+            response.moved = true;
+            let delta_x = input.orbit.x / input.window.x * std::f32::consts::PI * 2.0;
+            let delta_y = input.orbit.y / input.window.y * std::f32::consts::PI;
+            self.yaw -= delta_x;
+            // Pitch is clamped so we can never flip over the top and end up upside down.
+            self.pitch = (self.pitch - delta_y).clamp(settings.min_pitch, settings.max_pitch);
+        } else if input.pan.length_squared() > 0.0 {
+            response.moved = true;
+            response.panned = true;
+            // make panning distance independent of resolution and FOV, and proportional to distance away from focus point
+            let pan = input.pan * world_per_pixel(projection, input.window, self.radius);
+            // translate by local axes
+            let right = transform.rotation * Vec3::X * -pan.x;
+            let up = transform.rotation * Vec3::Y * pan.y;
+            self.focus += right + up;
+        } else if input.scroll.abs() > 0.0 {
+            response.moved = true;
+            let old_radius = self.radius;
+            self.radius -= input.scroll * self.radius * settings.wheel_sensitivity;
+            // dont allow zoom to reach zero or you get stuck
+            self.radius = self.radius.clamp(settings.min_radius, settings.max_radius);
+
+            // Zoom towards whatever is under the cursor, moving the focus by the same fraction
+            // the radius changed keeps that point under the cursor the whole way in (or out).
+            if let Some(hit) = input.cursor_hit {
+                let zoom_fraction = (old_radius - self.radius) / old_radius;
+                self.focus += (hit - self.focus) * zoom_fraction;
+            }
+        }
+
+        // Keyboard and edge pan follow where the camera is facing, flattened onto the ground.
+        let direction = (input.keys + input.edge).normalize_or_zero();
+        if direction != Vec2::ZERO {
+            response.moved = true;
+            response.panned = true;
+            let right = ground_projected(transform.right());
+            // Looking straight down forward has nothing left once flattened, so use up instead.
+            let mut forward = ground_projected(transform.forward());
+            if forward == Vec3::ZERO {
+                forward = ground_projected(transform.up());
+            }
+
+            self.focus += (right * direction.x + forward * direction.y)
+                * settings.pan_speed
+                * self.radius
+                * input.delta_seconds;
+        }
+
+        response
+    }
+}
+
+impl Default for TopDownCamera {
+    fn default() -> Self {
+        TopDownCamera {
+            focus: Vec3::ZERO,
+            half_height: 20.0,
+        }
+    }
+}
+
+impl CameraController for TopDownCamera {
+    fn from_view(focus: Vec3, transform: &Transform) -> Self {
+        TopDownCamera {
+            focus,
+            half_height: transform.translation.distance(focus),
+        }
+    }
+
+    fn projection(&self) -> Projection {
+        Projection::Orthographic(OrthographicProjection {
+            scale: self.half_height,
+            scaling_mode: ScalingMode::FixedVertical(2.0),
+            far: TOP_DOWN_HEIGHT * 2.0,
+            ..default()
+        })
+    }
+
+    fn focus(&self, _transform: &Transform) -> Vec3 {
+        self.focus
+    }
+
+    /// Always straight down with north up, zoom is the radius.
+    fn pose(&self, _transform: &Transform) -> OrbitPose {
+        OrbitPose {
+            focus: self.focus,
+            radius: self.half_height,
+            yaw: 0.0,
+            pitch: -std::f32::consts::FRAC_PI_2,
+        }
+    }
+
+    /// Yaw and pitch are ignored, a bookmark or jump only moves and zooms the top-down view.
+    fn set_pose(&mut self, pose: OrbitPose, transform: &mut Transform) {
+        self.focus = pose.focus;
+        self.half_height = pose.radius;
+        *transform = self.transform();
+    }
+
+    /// No pitch to limit, it's always looking straight down.
+    fn within_limits(pose: OrbitPose, settings: &CameraSettings) -> OrbitPose {
+        OrbitPose {
+            radius: pose.radius.clamp(settings.min_radius, settings.max_radius),
+            ..pose
+        }
+    }
+
+    fn apply_input(
+        &mut self,
+        input: &CameraInput,
+        settings: &CameraSettings,
+        transform: &mut Transform,
+        projection: &mut Projection,
+    ) -> ControllerResponse {
+        let mut response = ControllerResponse::default();
+
+        if input.pan.length_squared() > 0.0 {
+            response.moved = true;
+            response.panned = true;
+            // Orthographic, so a pixel is the same distance on the ground however far away it is.
+            let pan = input.pan * world_per_pixel(projection, input.window, 1.0);
+            self.focus += Vec3::new(-pan.x, 0.0, -pan.y);
+        } else if input.scroll.abs() > 0.0 {
+            response.moved = true;
+            let old_half_height = self.half_height;
+            self.half_height -= input.scroll * self.half_height * settings.wheel_sensitivity;
+            self.half_height = self
+                .half_height
+                .clamp(settings.min_radius, settings.max_radius);
+
+            // Same as the orbit camera, the view scales linearly so this keeps the cursor on the same spot.
+            if let Some(hit) = input.cursor_hit {
+                let zoom_fraction = (old_half_height - self.half_height) / old_half_height;
+                let hit = Vec3::new(hit.x, self.focus.y, hit.z);
+                self.focus += (hit - self.focus) * zoom_fraction;
+            }
+        }
+
+        // Screen up is north (-z) so pans don't need to care about rotation.
+        let direction = (input.keys + input.edge).normalize_or_zero();
+        if direction != Vec2::ZERO {
+            response.moved = true;
+            response.panned = true;
+            self.focus += Vec3::new(direction.x, 0.0, -direction.y)
+                * settings.pan_speed
+                * self.half_height
+                * input.delta_seconds;
+        }
+
+        if let Projection::Orthographic(projection) = projection {
+            if projection.scale != self.half_height {
+                projection.scale = self.half_height;
+            }
+        }
+        *transform = self.transform();
+
+        response
+    }
+}
+
+impl TopDownCamera {
+    fn transform(&self) -> Transform {
+        Transform::from_translation(self.focus + Vec3::Y * TOP_DOWN_HEIGHT)
+            .looking_at(self.focus, Vec3::NEG_Z)
+    }
+}
+
+impl CameraController for FreeFlyCamera {
+    fn from_view(_focus: Vec3, transform: &Transform) -> Self {
+        let (yaw, pitch, _) = transform.rotation.to_euler(EulerRot::YXZ);
+        FreeFlyCamera { yaw, pitch }
+    }
+
+    fn projection(&self) -> Projection {
+        Projection::Perspective(default())
+    }
+
+    /// Where we're looking hits the ground, or a bit in front of us if we're looking up.
+    fn focus(&self, transform: &Transform) -> Vec3 {
+        let forward = transform.forward();
+        if forward.y < -f32::EPSILON {
+            transform.translation + forward * (-transform.translation.y / forward.y)
+        } else {
+            let ahead = transform.translation + ground_projected(forward) * 10.0;
+            Vec3::new(ahead.x, 0.0, ahead.z)
+        }
+    }
+
+    /// Orbiting the ground where we're looking, or a point just ahead if that's not the ground,
+    /// so setting the pose back puts the camera exactly where it was.
+    fn pose(&self, transform: &Transform) -> OrbitPose {
+        let forward = transform.forward();
+        let distance = if forward.y < -f32::EPSILON {
+            -transform.translation.y / forward.y
+        } else {
+            10.0
+        };
+        OrbitPose {
+            focus: transform.translation + forward * distance,
+            radius: distance,
+            yaw: self.yaw,
+            pitch: self.pitch,
+        }
+    }
+
+    fn set_pose(&mut self, pose: OrbitPose, transform: &mut Transform) {
+        self.yaw = pose.yaw;
+        self.pitch = pose.pitch;
+        pose.apply_to(transform);
+    }
+
+    /// Flies at whatever height and angle it likes, only where it's looking is kept on the map.
+    fn within_limits(pose: OrbitPose, _settings: &CameraSettings) -> OrbitPose {
+        pose
+    }
+
+    fn apply_input(
+        &mut self,
+        input: &CameraInput,
+        settings: &CameraSettings,
+        transform: &mut Transform,
+        _projection: &mut Projection,
+    ) -> ControllerResponse {
+        let mut response = ControllerResponse::default();
+
+        if input.orbit.length_squared() > 0.0 {
+            response.moved = true;
+            let look = input.orbit / input.window * std::f32::consts::PI;
+            self.yaw -= look.x * 2.0;
+            // Just short of straight up/down, past that yaw flips around
+            let limit = 89f32.to_radians();
+            self.pitch = (self.pitch - look.y).clamp(-limit, limit);
+        }
+        transform.rotation = Quat::from_euler(EulerRot::YXZ, self.yaw, self.pitch, 0.0);
+
+        // Fly where we're looking, scrolling is a quick hop forwards or back
+        let movement = transform.right() * input.keys.x
+            + transform.forward() * input.keys.y
+            + Vec3::Y * input.vertical;
+        let mut translation =
+            movement.normalize_or_zero() * settings.fly_speed * input.delta_seconds;
+        translation += transform.forward() * input.scroll * settings.fly_speed * 0.1;
+        if translation != Vec3::ZERO {
+            response.moved = true;
+            transform.translation += translation;
+        }
+
+        response
+    }
+}
@@ -1,23 +1,61 @@
-use bevy::prelude::*;
+use bevy::{ecs::schedule::SystemConfigs, prelude::*};
 
 pub mod components;
-mod resources;
+pub mod controllers;
+mod picking;
+pub mod resources;
 mod systems;
 
+use crate::{intent::InputStage, world_gen::resources::ActiveScenario};
+use components::{FreeFlyCamera, TopDownCamera};
+use controllers::CameraController;
 use resources::*;
 use systems::*;
 //use components::*;
 
+pub use picking::screen_ray_to_entity;
+pub use systems::PanOrbitCamera;
+
 pub struct CameraPlugin;
 
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(CameraSettings {
-            rotation: 0.02,
-            pixels_per_line: 52.0,
-            wheel_sensitivity: 0.2,
-        })
-        .add_startup_system(spawn_camera)
-        .add_system(pan_orbit_camera);
+        app.insert_resource(CameraSettings::load(CAMERA_SETTINGS_PATH))
+            .init_resource::<CameraBookmarks>()
+            .add_event::<CameraJump>()
+            .add_startup_system(spawn_camera)
+            .init_resource::<CameraRigs>()
+            .add_system(switch_camera_rig.before(InputStage::World))
+            .add_systems(
+                (
+                    drive_camera::<PanOrbitCamera>,
+                    drive_camera::<TopDownCamera>,
+                    drive_camera::<FreeFlyCamera>,
+                )
+                    .in_set(InputStage::World),
+            )
+            .add_system(toggle_camera_follow)
+            .add_system(load_camera_bookmarks.run_if(resource_changed::<ActiveScenario>()))
+            .add_systems(rig_systems::<PanOrbitCamera>())
+            .add_systems(rig_systems::<TopDownCamera>())
+            .add_systems(rig_systems::<FreeFlyCamera>())
+            // Only the orbit camera eases its transform after the target, the others move it themselves
+            .add_system(update_camera_transform.after(clamp_camera_to_bounds::<PanOrbitCamera>));
     }
 }
+
+/// Bookmarks, jumps, following and staying on the map for rig `C`, all after the input's been
+/// applied.
+fn rig_systems<C: CameraController>() -> SystemConfigs {
+    (
+        camera_bookmarks::<C>,
+        start_camera_jumps::<C>,
+        follow_unit::<C>,
+        run_camera_transitions::<C>,
+        clamp_camera_to_bounds::<C>,
+    )
+        .chain()
+        .after(InputStage::World)
+        .after(toggle_camera_follow)
+        .after(load_camera_bookmarks)
+}
@@ -0,0 +1,30 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+/// Works for orthographic cameras too, their rays start on the near plane under the cursor
+/// rather than all coming from the camera's position.
+pub fn screen_ray_to_entity(
+    camera: &Camera,
+    rapier_context: &RapierContext,
+    camera_location: &GlobalTransform,
+    cursor_position: Vec2,
+) -> Option<(Entity, Vec3)> {
+    if let Some(check_ray) = camera.viewport_to_world(camera_location, cursor_position) {
+        let max_toi = 5000.0; // Hard value probably shouldn't be
+        let solid = true;
+        let filter = QueryFilter::default(); //TODO: Don't filter because we want to hit anything, we might want to filter out invisible stuff like sensors.
+
+        if let Some((entity, toi)) = rapier_context.cast_ray(
+            check_ray.origin,
+            check_ray.direction,
+            max_toi,
+            solid,
+            filter,
+        ) {
+            let hit_point = check_ray.origin + check_ray.direction * toi;
+            return Some((entity, hit_point));
+        }
+    }
+
+    None
+}
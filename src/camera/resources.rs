@@ -1,8 +1,257 @@
-use bevy::prelude::*;
+use std::path::Path;
 
-#[derive(Resource)]
+use bevy::{asset::FileAssetIo, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use super::components::OrbitPose;
+use crate::{
+    intent::resources::PointerIntents,
+    save::{read_ron, write_ron},
+};
+
+/// Where the camera settings live, relative to the same base path bevy finds the `assets` folder in.
+pub const CAMERA_SETTINGS_PATH: &str = "assets/settings/camera.ron";
+
+/// Bookmarks are saved in the scenario's save folder under this name.
+pub const CAMERA_BOOKMARKS_FILE: &str = "camera_bookmarks.ron";
+
+#[derive(Resource, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct CameraSettings {
-    pub rotation: f32,
+    /// Orbit speed, at 1.0 dragging across the full width of the window is one full turn.
+    pub orbit_sensitivity: f32,
+    /// Middle mouse pan speed, at 1.0 the ground under the cursor roughly follows the cursor.
+    pub pan_sensitivity: f32,
+    /// Pixel based scroll wheels (touchpads) report pixels, this turns them back into lines.
     pub pixels_per_line: f32,
+    /// How much of the radius one line of scroll zooms by.
     pub wheel_sensitivity: f32,
+    /// Zoom towards what's under the cursor instead of straight at the focus.
+    pub zoom_to_cursor: bool,
+    /// How fast the keyboard/edge pan moves, scaled by the camera radius so it feels the same zoomed in or out.
+    pub pan_speed: f32,
+    pub edge_scroll: bool,
+    /// How close in pixels the cursor has to be to the window edge to start scrolling.
+    pub edge_scroll_margin: f32,
+    pub min_radius: f32,
+    pub max_radius: f32,
+    /// Pitch limits in radians, negative is looking down at the ground.
+    pub min_pitch: f32,
+    pub max_pitch: f32,
+    /// Ease the focus back inside the map at this speed instead of hard stopping at the edge.
+    pub bounds_snap_back: Option<f32>,
+    /// How quickly the view catches up with pans, zooms and orbits, 0 is instant.
+    pub pan_damping: f32,
+    pub zoom_damping: f32,
+    pub orbit_damping: f32,
+    /// How long a jump (to a unit, minimap click) takes.
+    pub transition_seconds: f32,
+    /// Free-fly camera speed in units per second.
+    pub fly_speed: f32,
+    pub input: CameraInputMap,
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        CameraSettings {
+            orbit_sensitivity: 1.0,
+            pan_sensitivity: 1.0,
+            pixels_per_line: 52.0,
+            wheel_sensitivity: 0.2,
+            zoom_to_cursor: true,
+            pan_speed: 1.0,
+            edge_scroll: true,
+            edge_scroll_margin: 10.0,
+            min_radius: 2.0,
+            max_radius: 80.0,
+            min_pitch: -89f32.to_radians(),
+            max_pitch: -5f32.to_radians(),
+            bounds_snap_back: Some(8.0),
+            pan_damping: 12.0,
+            zoom_damping: 10.0,
+            orbit_damping: 0.0,
+            transition_seconds: 0.6,
+            fly_speed: 20.0,
+            input: CameraInputMap::default(),
+        }
+    }
+}
+
+impl CameraSettings {
+    /// Load the settings file, anything missing from it falls back to the defaults and
+    /// if it's missing entirely (or broken) we just use the defaults.
+    pub fn load(path: impl AsRef<Path>) -> Self {
+        let path = FileAssetIo::get_base_path().join(path);
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(err) => {
+                info!("No camera settings at {path:?} ({err}), using defaults");
+                return CameraSettings::default();
+            }
+        };
+
+        ron::from_str(&contents).unwrap_or_else(|err| {
+            error!("Failed to parse camera settings {path:?}: {err}");
+            CameraSettings::default()
+        })
+    }
+}
+
+/// Move the camera somewhere with an eased transition, anything left as `None` stays as it is.
+pub struct CameraJump {
+    pub focus: Vec3,
+    pub radius: Option<f32>,
+    pub yaw: Option<f32>,
+    pub pitch: Option<f32>,
+}
+
+impl CameraJump {
+    pub fn to(focus: Vec3) -> Self {
+        CameraJump {
+            focus,
+            radius: None,
+            yaw: None,
+            pitch: None,
+        }
+    }
+
+    pub fn to_pose(pose: OrbitPose) -> Self {
+        CameraJump {
+            focus: pose.focus,
+            radius: Some(pose.radius),
+            yaw: Some(pose.yaw),
+            pitch: Some(pose.pitch),
+        }
+    }
+}
+
+/// The different ways the player's camera can be controlled.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CameraRig {
+    #[default]
+    PanOrbit,
+    TopDown,
+    FreeFly,
+}
+
+impl CameraRig {
+    pub fn next(self) -> CameraRig {
+        match self {
+            CameraRig::PanOrbit => CameraRig::TopDown,
+            CameraRig::TopDown => CameraRig::FreeFly,
+            CameraRig::FreeFly => CameraRig::PanOrbit,
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct CameraRigs {
+    pub active: CameraRig,
+    /// The orbit camera's pose when we switched away from it, so coming back keeps the same angle and zoom.
+    pub parked: Option<OrbitPose>,
+}
+
+/// Saved camera positions, one slot per key in `CameraInputMap::bookmarks`.
+#[derive(Resource, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CameraBookmarks {
+    pub slots: Vec<Option<OrbitPose>>,
+}
+
+impl CameraBookmarks {
+    /// Missing or broken files just mean no bookmarks yet.
+    pub fn load(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        // Nothing's been bookmarked in this scenario, not worth an error
+        if !path.exists() {
+            return CameraBookmarks::default();
+        }
+
+        read_ron(path).unwrap_or_default()
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) {
+        write_ron(path, self);
+    }
+
+    pub fn get(&self, slot: usize) -> Option<OrbitPose> {
+        self.slots.get(slot).copied().flatten()
+    }
+
+    pub fn set(&mut self, slot: usize, pose: OrbitPose) {
+        if self.slots.len() <= slot {
+            self.slots.resize(slot + 1, None);
+        }
+        self.slots[slot] = Some(pose);
+    }
+}
+
+/// Which buttons and keys drive the camera.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CameraInputMap {
+    pub orbit_button: MouseButton,
+    /// Key that has to be held along with the orbit button, if any.
+    pub orbit_modifier: Option<KeyCode>,
+    pub pan_button: MouseButton,
+    pub pan_modifier: Option<KeyCode>,
+    pub pan_forward: Vec<KeyCode>,
+    pub pan_back: Vec<KeyCode>,
+    pub pan_left: Vec<KeyCode>,
+    pub pan_right: Vec<KeyCode>,
+    /// Follow the selected unit, pressing again (or panning) stops.
+    pub follow: KeyCode,
+    /// Pressing one of these jumps to that bookmark, holding the save modifier as well stores it.
+    pub bookmarks: Vec<KeyCode>,
+    pub bookmark_save_modifier: KeyCode,
+    /// Cycle between the orbit, top-down and free-fly cameras.
+    pub switch_rig: KeyCode,
+    pub fly_up: Vec<KeyCode>,
+    pub fly_down: Vec<KeyCode>,
+    pub invert_orbit_x: bool,
+    pub invert_orbit_y: bool,
+    pub invert_pan: bool,
+    pub invert_zoom: bool,
+}
+
+impl Default for CameraInputMap {
+    fn default() -> Self {
+        CameraInputMap {
+            orbit_button: MouseButton::Right,
+            orbit_modifier: None,
+            pan_button: MouseButton::Middle,
+            pan_modifier: None,
+            pan_forward: vec![KeyCode::W, KeyCode::Up],
+            pan_back: vec![KeyCode::S, KeyCode::Down],
+            pan_left: vec![KeyCode::A, KeyCode::Left],
+            pan_right: vec![KeyCode::D, KeyCode::Right],
+            follow: KeyCode::F,
+            bookmarks: vec![KeyCode::F1, KeyCode::F2, KeyCode::F3, KeyCode::F4],
+            bookmark_save_modifier: KeyCode::LControl,
+            switch_rig: KeyCode::F8,
+            fly_up: vec![KeyCode::E],
+            fly_down: vec![KeyCode::Q],
+            invert_orbit_x: false,
+            invert_orbit_y: false,
+            invert_pan: false,
+            invert_zoom: false,
+        }
+    }
+}
+
+impl CameraInputMap {
+    /// Only drags move the camera, a click on the same button is left for orders and selection.
+    pub fn orbiting(&self, intents: &PointerIntents, keys: &Input<KeyCode>) -> bool {
+        intents.dragging(self.orbit_button) && modifier_held(self.orbit_modifier, keys)
+    }
+
+    pub fn panning(&self, intents: &PointerIntents, keys: &Input<KeyCode>) -> bool {
+        intents.dragging(self.pan_button) && modifier_held(self.pan_modifier, keys)
+    }
+}
+
+fn modifier_held(modifier: Option<KeyCode>, keys: &Input<KeyCode>) -> bool {
+    match modifier {
+        Some(key) => keys.pressed(key),
+        None => true,
+    }
 }
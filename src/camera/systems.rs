@@ -1,10 +1,16 @@
-use super::{components::PlayerCamera, resources::CameraSettings};
+use super::{components::*, controllers::*, resources::*, screen_ray_to_entity};
+use crate::{
+    intent::resources::PointerIntents,
+    selection::components::SelectedUnit,
+    world_gen::resources::{ActiveScenario, Ground},
+};
 use bevy::{
     input::mouse::{MouseMotion, MouseScrollUnit, MouseWheel},
     prelude::*,
     window::PrimaryWindow,
 };
 use bevy_mod_picking::prelude::*;
+use bevy_rapier3d::prelude::RapierContext;
 
 // Updated to use pan orbit camera from https://bevy-cheatbook.github.io/cookbook/pan-orbit-camera.html
 // I didn't feel like digging into the movement with removing the rotation of the camera.
@@ -15,7 +21,10 @@ pub struct PanOrbitCamera {
     /// The "focus point" to orbit around. It is automatically updated when panning the camera
     pub focus: Vec3,
     pub radius: f32,
-    pub upside_down: bool,
+    /// Rotation around the world y axis, in radians.
+    pub yaw: f32,
+    /// Rotation around the camera's x axis, in radians, negative is looking down.
+    pub pitch: f32,
 }
 
 impl Default for PanOrbitCamera {
@@ -23,124 +32,496 @@ impl Default for PanOrbitCamera {
         PanOrbitCamera {
             focus: Vec3::ZERO,
             radius: 5.0,
-            upside_down: false,
+            yaw: 0.0,
+            pitch: -std::f32::consts::FRAC_PI_4,
         }
     }
 }
 
-/// Pan the camera with middle mouse click, zoom with scroll wheel, orbit with right mouse click.
-pub fn pan_orbit_camera(
-    windows: Query<&Window, With<PrimaryWindow>>,
-    mut ev_motion: EventReader<MouseMotion>,
-    mut ev_scroll: EventReader<MouseWheel>,
-    input_mouse: Res<Input<MouseButton>>,
-    mut query: Query<(&mut PanOrbitCamera, &mut Transform, &Projection)>,
-) {
-    // change input mapping for orbit and panning here
-    let orbit_button = MouseButton::Right;
-    let pan_button = MouseButton::Middle;
+impl PanOrbitCamera {
+    pub fn pose(&self) -> OrbitPose {
+        OrbitPose {
+            focus: self.focus,
+            radius: self.radius,
+            yaw: self.yaw,
+            pitch: self.pitch,
+        }
+    }
 
-    let mut pan = Vec2::ZERO;
-    let mut rotation_move = Vec2::ZERO;
-    let mut scroll = 0.0;
-    let mut orbit_button_changed = false;
+    pub fn set_pose(&mut self, pose: OrbitPose) {
+        self.focus = pose.focus;
+        self.radius = pose.radius;
+        self.yaw = pose.yaw;
+        self.pitch = pose.pitch;
+    }
+}
+
+/// Gather this frame's camera input, buttons and sensitivities come from `CameraSettings`
+/// (by default pan with middle mouse click, zoom with scroll wheel, orbit with right mouse click,
+/// pan keys are WASD/arrows or pushing the cursor against the edge of the window).
+fn read_camera_input(
+    windows: &Query<&Window, With<PrimaryWindow>>,
+    ev_motion: &mut EventReader<MouseMotion>,
+    ev_scroll: &mut EventReader<MouseWheel>,
+    intents: &PointerIntents,
+    input_keys: &Input<KeyCode>,
+    settings: &CameraSettings,
+    delta_seconds: f32,
+) -> CameraInput {
+    let input = &settings.input;
+    let mut camera_input = CameraInput {
+        window: get_primary_window_size(windows),
+        delta_seconds,
+        ..default()
+    };
 
-    if input_mouse.pressed(orbit_button) {
+    if input.orbiting(intents, input_keys) {
         for ev in ev_motion.iter() {
-            rotation_move += ev.delta;
+            camera_input.orbit += ev.delta;
         }
-    } else if input_mouse.pressed(pan_button) {
+    } else if input.panning(intents, input_keys) {
         // Pan only if we're not rotating at the moment
         for ev in ev_motion.iter() {
-            pan += ev.delta;
+            camera_input.pan += ev.delta;
         }
     }
     for ev in ev_scroll.iter() {
-        scroll += ev.y;
+        // Normalise everything to lines so touchpads and wheels zoom at the same rate
+        camera_input.scroll += match ev.unit {
+            MouseScrollUnit::Line => ev.y,
+            MouseScrollUnit::Pixel => ev.y / settings.pixels_per_line,
+        };
+    }
+
+    if input.invert_orbit_x {
+        camera_input.orbit.x = -camera_input.orbit.x;
+    }
+    if input.invert_orbit_y {
+        camera_input.orbit.y = -camera_input.orbit.y;
     }
-    if input_mouse.just_released(orbit_button) || input_mouse.just_pressed(orbit_button) {
-        orbit_button_changed = true;
+    if input.invert_pan {
+        camera_input.pan = -camera_input.pan;
     }
+    if input.invert_zoom {
+        camera_input.scroll = -camera_input.scroll;
+    }
+    camera_input.orbit *= settings.orbit_sensitivity;
+    camera_input.pan *= settings.pan_sensitivity;
 
-    for (mut pan_orbit, mut transform, projection) in query.iter_mut() {
-        if orbit_button_changed {
-            // only check for upside down when orbiting started or ended this frame
-            // if the camera is "upside" down, panning horizontally would be inverted, so invert the input to make it correct
-            let up = transform.rotation * Vec3::Y;
-            pan_orbit.upside_down = up.y <= 0.0;
+    let axis = |negative: &[KeyCode], positive: &[KeyCode]| {
+        let mut value = 0.0;
+        if input_keys.any_pressed(positive.iter().copied()) {
+            value += 1.0;
+        }
+        if input_keys.any_pressed(negative.iter().copied()) {
+            value -= 1.0;
         }
+        value
+    };
+    camera_input.keys = Vec2::new(
+        axis(&input.pan_left, &input.pan_right),
+        axis(&input.pan_back, &input.pan_forward),
+    );
+    camera_input.vertical = axis(&input.fly_down, &input.fly_up);
 
-        let mut any = false;
-        if rotation_move.length_squared() > 0.0 {
-            any = true;
-            let window = get_primary_window_size(&windows);
-            let delta_x = {
-                let delta = rotation_move.x / window.x * std::f32::consts::PI * 2.0;
-                if pan_orbit.upside_down {
-                    -delta
-                } else {
-                    delta
+    if settings.edge_scroll {
+        if let Ok(window) = windows.get_single() {
+            // Don't scroll away when the cursor is just resting on the edge of an unfocused window.
+            if let (true, Some(cursor)) = (window.focused, window.cursor_position()) {
+                let margin = settings.edge_scroll_margin;
+                if cursor.x <= margin {
+                    camera_input.edge.x -= 1.0;
+                } else if cursor.x >= window.width() - margin {
+                    camera_input.edge.x += 1.0;
+                }
+                // Cursor position starts at the bottom of the window
+                if cursor.y <= margin {
+                    camera_input.edge.y -= 1.0;
+                } else if cursor.y >= window.height() - margin {
+                    camera_input.edge.y += 1.0;
                 }
-            };
-            let delta_y = rotation_move.y / window.y * std::f32::consts::PI;
-            let yaw = Quat::from_rotation_y(-delta_x);
-            let pitch = Quat::from_rotation_x(-delta_y);
-            transform.rotation *= yaw; // rotate around global y axis
-            transform.rotation *= pitch; // rotate around local x axis
-        } else if pan.length_squared() > 0.0 {
-            any = true;
-            // make panning distance independent of resolution and FOV,
-            let window = get_primary_window_size(&windows);
-            if let Projection::Perspective(projection) = projection {
-                pan *= Vec2::new(projection.fov * projection.aspect_ratio, projection.fov) / window;
             }
-            // translate by local axes
-            let right = transform.rotation * Vec3::X * -pan.x;
-            let up = transform.rotation * Vec3::Y * pan.y;
-            // make panning proportional to distance away from focus point
-            let translation = (right + up) * pan_orbit.radius;
-            pan_orbit.focus += translation;
-        } else if scroll.abs() > 0.0 {
-            any = true;
-            pan_orbit.radius -= scroll * pan_orbit.radius * 0.2;
-            // dont allow zoom to reach zero or you get stuck
-            pan_orbit.radius = f32::max(pan_orbit.radius, 0.05);
-        }
-
-        if any {
-            // emulating parent/child to make the yaw/y-axis rotation behave like a turntable
-            // parent = x and y rotation
-            // child = z-offset
-            let rot_matrix = Mat3::from_quat(transform.rotation);
-            transform.translation =
-                pan_orbit.focus + rot_matrix.mul_vec3(Vec3::new(0.0, 0.0, pan_orbit.radius));
-        }
-    }
-
-    // consume any remaining events, so they don't pile up if we don't need them
+        }
+    }
+
+    camera_input
+}
+
+/// Feed the frame's input to every camera using rig `C`.
+#[allow(clippy::too_many_arguments)]
+pub fn drive_camera<C: CameraController>(
+    mut commands: Commands,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut ev_motion: EventReader<MouseMotion>,
+    mut ev_scroll: EventReader<MouseWheel>,
+    intents: Res<PointerIntents>,
+    input_keys: Res<Input<KeyCode>>,
+    time: Res<Time>,
+    settings: Res<CameraSettings>,
+    rapier_context: Res<RapierContext>,
+    mut query: Query<(
+        Entity,
+        &mut C,
+        &mut Transform,
+        &mut Projection,
+        &Camera,
+        &GlobalTransform,
+    )>,
+) {
+    if query.is_empty() {
+        // consume any remaining events, so they don't pile up if we don't need them
+        ev_motion.clear();
+        ev_scroll.clear();
+        return;
+    }
+
+    let mut input = read_camera_input(
+        &windows,
+        &mut ev_motion,
+        &mut ev_scroll,
+        &intents,
+        &input_keys,
+        &settings,
+        time.delta_seconds(),
+    );
     // (and also to avoid Bevy warning us about not checking events every frame update)
     ev_motion.clear();
+
+    for (entity, mut controller, mut transform, mut projection, camera, camera_location) in
+        query.iter_mut()
+    {
+        input.cursor_hit = None;
+        let cursor = windows.get_single().ok().and_then(|w| w.cursor_position());
+        if let (true, true, Some(cursor)) = (settings.zoom_to_cursor, input.scroll != 0.0, cursor) {
+            input.cursor_hit =
+                screen_ray_to_entity(camera, &rapier_context, camera_location, cursor)
+                    .map(|(_, hit)| hit);
+        }
+
+        let response = controller.apply_input(&input, &settings, &mut transform, &mut projection);
+        // Panning away is how you stop following something
+        if response.panned {
+            commands.entity(entity).remove::<CameraFollow>();
+        }
+        if response.moved {
+            // The player has taken over, so whatever transition was playing is done
+            commands.entity(entity).remove::<CameraTransition>();
+        }
+    }
+}
+
+/// Cycle the player's camera to the next rig, starting it over the same spot on the ground.
+#[allow(clippy::type_complexity)]
+pub fn switch_camera_rig(
+    mut commands: Commands,
+    input_keys: Res<Input<KeyCode>>,
+    settings: Res<CameraSettings>,
+    mut rigs: ResMut<CameraRigs>,
+    query: Query<
+        (
+            Entity,
+            &Transform,
+            Option<&PanOrbitCamera>,
+            Option<&TopDownCamera>,
+            Option<&FreeFlyCamera>,
+        ),
+        With<PlayerCamera>,
+    >,
+) {
+    if !input_keys.just_pressed(settings.input.switch_rig) {
+        return;
+    }
+    let Ok((entity, transform, pan_orbit, top_down, free_fly)) = query.get_single() else { return; };
+
+    let focus = match (pan_orbit, top_down, free_fly) {
+        (Some(rig), _, _) => rig.focus(transform),
+        (_, Some(rig), _) => rig.focus(transform),
+        (_, _, Some(rig)) => rig.focus(transform),
+        _ => Vec3::ZERO,
+    };
+    if let Some(pan_orbit) = pan_orbit {
+        rigs.parked = Some(pan_orbit.pose());
+    }
+
+    let mut camera = commands.entity(entity);
+    camera.remove::<(
+        PanOrbitCamera,
+        SmoothedOrbit,
+        CameraTransition,
+        CameraFollow,
+        TopDownCamera,
+        FreeFlyCamera,
+    )>();
+
+    rigs.active = rigs.active.next();
+    match rigs.active {
+        CameraRig::PanOrbit => {
+            let mut pan_orbit = PanOrbitCamera::from_view(focus, transform);
+            if let Some(mut parked) = rigs.parked {
+                parked.focus = focus;
+                pan_orbit.set_pose(parked);
+            }
+            camera.insert((
+                pan_orbit.projection(),
+                SmoothedOrbit(pan_orbit.pose()),
+                pan_orbit,
+            ));
+        }
+        CameraRig::TopDown => {
+            let top_down = TopDownCamera::from_view(focus, transform);
+            camera.insert((top_down.projection(), top_down));
+        }
+        CameraRig::FreeFly => {
+            let free_fly = FreeFlyCamera::from_view(focus, transform);
+            camera.insert((free_fly.projection(), free_fly));
+        }
+    }
+    info!("Switched camera to {:?}", rigs.active);
 }
 
-fn get_primary_window_size(primary_window: &Query<&Window, With<PrimaryWindow>>) -> Vec2 {
-    let window = primary_window.single();
-    Vec2::new(window.width(), window.height())
+/// Toggle following the first selected unit, every rig can follow.
+pub fn toggle_camera_follow(
+    mut commands: Commands,
+    input_keys: Res<Input<KeyCode>>,
+    settings: Res<CameraSettings>,
+    selected: Query<Entity, With<SelectedUnit>>,
+    query: Query<(Entity, Option<&CameraFollow>), With<PlayerCamera>>,
+) {
+    if !input_keys.just_pressed(settings.input.follow) {
+        return;
+    }
+
+    for (entity, following) in query.iter() {
+        if following.is_some() {
+            commands.entity(entity).remove::<CameraFollow>();
+        } else if let Some(unit) = selected.iter().next() {
+            commands.entity(entity).insert(CameraFollow(unit));
+        }
+    }
+}
+
+/// Swap in the bookmarks for whichever scenario is now being played.
+pub fn load_camera_bookmarks(mut commands: Commands, scenario: Res<ActiveScenario>) {
+    let path = scenario.save_dir().join(CAMERA_BOOKMARKS_FILE);
+    commands.insert_resource(CameraBookmarks::load(path));
+}
+
+/// Bookmark hotkeys for whichever rig is active, save the current view with the modifier held or
+/// jump back to one without.
+pub fn camera_bookmarks<C: CameraController>(
+    input_keys: Res<Input<KeyCode>>,
+    settings: Res<CameraSettings>,
+    scenario: Res<ActiveScenario>,
+    mut bookmarks: ResMut<CameraBookmarks>,
+    mut jumps: EventWriter<CameraJump>,
+    query: Query<(&C, &Transform)>,
+) {
+    // Not the active rig, one of the others will handle it
+    let Ok((controller, transform)) = query.get_single() else { return; };
+
+    let input = &settings.input;
+    let Some(slot) = input
+        .bookmarks
+        .iter()
+        .position(|key| input_keys.just_pressed(*key))
+    else {
+        return;
+    };
+
+    if input_keys.pressed(input.bookmark_save_modifier) {
+        bookmarks.set(slot, controller.pose(transform));
+        bookmarks.save(scenario.save_dir().join(CAMERA_BOOKMARKS_FILE));
+    } else if let Some(pose) = bookmarks.get(slot) {
+        jumps.send(CameraJump::to_pose(pose));
+    }
+}
+
+pub fn follow_unit<C: CameraController>(
+    mut commands: Commands,
+    units: Query<&GlobalTransform>,
+    mut query: Query<(Entity, &mut C, &mut Transform, &CameraFollow)>,
+) {
+    for (entity, mut controller, mut transform, follow) in query.iter_mut() {
+        match units.get(follow.0) {
+            Ok(unit) => controller.set_focus(unit.translation(), &mut transform),
+            // It's gone (died or despawned) so there's nothing to follow
+            Err(_) => {
+                commands.entity(entity).remove::<CameraFollow>();
+            }
+        }
+    }
+}
+
+/// Start an eased move to somewhere else, used for jumping to units and minimap clicks.
+pub fn start_camera_jumps<C: CameraController>(
+    mut commands: Commands,
+    mut jumps: EventReader<CameraJump>,
+    settings: Res<CameraSettings>,
+    query: Query<(Entity, &C, &Transform, Option<&SmoothedOrbit>)>,
+) {
+    let Some(jump) = jumps.iter().last() else { return; };
+
+    for (entity, controller, transform, smoothed) in query.iter() {
+        // Start from what's on screen rather than where the camera was heading
+        let from = smoothed
+            .map(|s| s.0)
+            .unwrap_or_else(|| controller.pose(transform));
+        let mut to = controller.pose(transform);
+        to.focus = jump.focus;
+        if let Some(radius) = jump.radius {
+            to.radius = radius;
+        }
+        if let Some(yaw) = jump.yaw {
+            to.yaw = yaw;
+        }
+        if let Some(pitch) = jump.pitch {
+            to.pitch = pitch;
+        }
+
+        commands
+            .entity(entity)
+            .remove::<CameraFollow>()
+            .insert(CameraTransition {
+                from,
+                to,
+                elapsed: 0.0,
+                duration: settings.transition_seconds,
+            });
+    }
+}
+
+pub fn run_camera_transitions<C: CameraController>(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(
+        Entity,
+        &mut C,
+        &mut Transform,
+        &mut CameraTransition,
+        Option<&mut SmoothedOrbit>,
+    )>,
+) {
+    for (entity, mut controller, mut transform, mut transition, smoothed) in query.iter_mut() {
+        transition.elapsed += time.raw_delta_seconds();
+        let t = (transition.elapsed / transition.duration.max(f32::EPSILON)).min(1.0);
+        // Smoothstep so it eases in and out
+        let eased = t * t * (3.0 - 2.0 * t);
+        let pose = transition.from.lerp(&transition.to, eased);
+
+        controller.set_pose(pose, &mut transform);
+        // The transition does its own easing, don't lag behind it as well
+        if let Some(mut smoothed) = smoothed {
+            smoothed.0 = pose;
+        }
+
+        if t >= 1.0 {
+            commands.entity(entity).remove::<CameraTransition>();
+        }
+    }
+}
+
+/// Keep the focus over the ground so the camera can't wander off the map, whichever rig it is.
+/// With snap back enabled the focus is eased back inside instead of stopping dead at the edge.
+pub fn clamp_camera_to_bounds<C: CameraController>(
+    time: Res<Time>,
+    ground: Res<Ground>,
+    settings: Res<CameraSettings>,
+    mut query: Query<(&mut C, &mut Transform)>,
+) {
+    let half_size = ground.size as f32 / 2.0;
+    let min = Vec3::new(-half_size, f32::MIN, -half_size);
+    let max = Vec3::new(half_size, f32::MAX, half_size);
+
+    for (mut controller, mut transform) in query.iter_mut() {
+        let pose = controller.pose(&transform);
+        let clamped = pose.focus.clamp(min, max);
+        let mut limited = C::within_limits(pose, &settings);
+
+        if clamped == pose.focus && limited == pose {
+            continue;
+        }
+
+        limited.focus = match settings.bounds_snap_back {
+            Some(speed) => {
+                let snapped = pose
+                    .focus
+                    .lerp(clamped, (speed * time.delta_seconds()).min(1.0));
+                // Close enough, stop creeping towards the edge forever
+                if snapped.distance(clamped) < 0.01 {
+                    clamped
+                } else {
+                    snapped
+                }
+            }
+            None => clamped,
+        };
+        controller.set_pose(limited, &mut transform);
+    }
+}
+
+/// Move the camera transform towards where the `PanOrbitCamera` wants to be. With damping at 0
+/// it's instant, higher is snappier, anything in between gives pans and zooms a bit of glide.
+pub fn update_camera_transform(
+    time: Res<Time>,
+    settings: Res<CameraSettings>,
+    mut query: Query<(&PanOrbitCamera, &mut Transform, Option<&mut SmoothedOrbit>)>,
+) {
+    // Raw time, so the camera keeps moving even if the game is paused or slowed down
+    let dt = time.raw_delta_seconds();
+    let blend = |damping: f32| {
+        if damping <= 0.0 {
+            1.0
+        } else {
+            1.0 - (-damping * dt).exp()
+        }
+    };
+
+    for (pan_orbit, mut transform, smoothed) in query.iter_mut() {
+        let target = pan_orbit.pose();
+        let Some(mut smoothed) = smoothed else {
+            target.apply_to(&mut transform);
+            continue;
+        };
+
+        let current = smoothed.0;
+        let orbit = current.lerp(&target, blend(settings.orbit_damping));
+        smoothed.0 = OrbitPose {
+            focus: current
+                .focus
+                .lerp(target.focus, blend(settings.pan_damping)),
+            radius: current.radius
+                + (target.radius - current.radius) * blend(settings.zoom_damping),
+            yaw: orbit.yaw,
+            pitch: orbit.pitch,
+        };
+        smoothed.0.apply_to(&mut transform);
+    }
 }
 
 /// Spawn a camera like this
 pub fn spawn_camera(mut commands: Commands) {
     let translation = Vec3::new(5.0, 20.0, 5.0);
     let radius = translation.length();
+    let transform = Transform::from_translation(translation).looking_at(Vec3::ZERO, Vec3::Y);
+    let (yaw, pitch, _) = transform.rotation.to_euler(EulerRot::YXZ);
 
     commands.spawn((
         Camera3dBundle {
-            transform: Transform::from_translation(translation).looking_at(Vec3::ZERO, Vec3::Y),
+            transform,
             ..Default::default()
         },
         PanOrbitCamera {
             radius,
+            yaw,
+            pitch,
             ..Default::default()
         },
+        SmoothedOrbit(OrbitPose {
+            focus: Vec3::ZERO,
+            radius,
+            yaw,
+            pitch,
+        }),
         PlayerCamera,
         RaycastPickCamera::default(),
         Name::new("PlayerCamera3d"),
@@ -0,0 +1,82 @@
+use std::path::PathBuf;
+
+use crate::world_gen::resources::SCENARIOS;
+
+pub const USAGE: &str = "\
+Usage: crate [options]
+
+  --seed <n>         Start the scenario from this seed instead of a random one
+  --scenario <name>  Scenario to play (skirmish, outpost or swarm), picks the save folder
+  --replay <file>    Play back a replay
+
+Headless, runs the simulation without a window then prints how it went:
+  --headless         Run without a window
+  --ticks <n>        Ticks to simulate, defaults to 1200 (a minute) or the end of the replay
+  --load <file>      Start from a save instead of the seed
+  --dump <file>      Save the game to this file when done";
+
+/// Ticks the headless runner simulates when not told otherwise, a minute of game time.
+pub const DEFAULT_HEADLESS_TICKS: u64 = 1200;
+
+#[derive(Debug, Default)]
+pub struct CliOptions {
+    pub help: bool,
+    pub headless: bool,
+    pub ticks: Option<u64>,
+    pub seed: Option<u64>,
+    pub scenario: Option<String>,
+    pub load: Option<PathBuf>,
+    pub replay: Option<PathBuf>,
+    pub dump: Option<PathBuf>,
+}
+
+impl CliOptions {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<CliOptions, String> {
+        let mut options = CliOptions::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("{arg} needs a value"));
+            match arg.as_str() {
+                "-h" | "--help" => options.help = true,
+                "--headless" => options.headless = true,
+                "--ticks" => options.ticks = Some(parse_number(&arg, value()?)?),
+                "--seed" => options.seed = Some(parse_number(&arg, value()?)?),
+                "--scenario" => options.scenario = Some(parse_scenario(value()?)?),
+                "--load" => options.load = Some(value()?.into()),
+                "--replay" => options.replay = Some(value()?.into()),
+                "--dump" => options.dump = Some(value()?.into()),
+                _ => return Err(format!("Unknown argument {arg}")),
+            }
+        }
+
+        if !options.headless
+            && (options.ticks.is_some() || options.load.is_some() || options.dump.is_some())
+        {
+            return Err("--ticks, --load and --dump only work with --headless".to_string());
+        }
+        if options.load.is_some() && options.replay.is_some() {
+            return Err("Replays play from the seed, they can't start from a save".to_string());
+        }
+
+        Ok(options)
+    }
+}
+
+/// Only scenarios we have, an unknown one would play the default but save under its own name.
+fn parse_scenario(name: String) -> Result<String, String> {
+    if SCENARIOS.iter().any(|scenario| scenario.name == name) {
+        return Ok(name);
+    }
+    let known: Vec<_> = SCENARIOS.iter().map(|scenario| scenario.name).collect();
+    Err(format!(
+        "Unknown scenario {name}, pick one of {}",
+        known.join(", ")
+    ))
+}
+
+fn parse_number(arg: &str, value: String) -> Result<u64, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{arg} wants a number, got {value}"))
+}
@@ -0,0 +1,28 @@
+use bevy::prelude::*;
+
+#[derive(Component)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Health { current: max, max }
+    }
+}
+
+/// How hard and how far a unit can hit, cooldown is in seconds between hits.
+#[derive(Component)]
+pub struct UnitAttack {
+    pub range: f32,
+    pub damage: f32,
+    pub cooldown: f32,
+}
+
+/// Order a unit to chase and attack another entity until one of them is gone.
+#[derive(Component)]
+pub struct AttackTarget(pub Entity);
+
+#[derive(Component, Default)]
+pub struct AttackCooldown(pub f32);
@@ -0,0 +1,23 @@
+use bevy::prelude::*;
+
+use crate::simulation::SimulationSet;
+
+pub mod components;
+pub mod resources;
+mod systems;
+
+use resources::*;
+use systems::*;
+
+pub struct CombatPlugin;
+
+impl Plugin for CombatPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DamageEvent>().add_systems(
+            (attack_target, apply_damage, despawn_dead)
+                .chain()
+                .in_set(SimulationSet::Combat)
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
+    }
+}
@@ -0,0 +1,6 @@
+use bevy::prelude::*;
+
+pub struct DamageEvent {
+    pub target: Entity,
+    pub amount: f32,
+}
@@ -0,0 +1,83 @@
+use bevy::prelude::*;
+
+use crate::{
+    orders::components::HoldPosition, simulation::resources::SimulationClock,
+    units::components::WalkToLocation,
+};
+
+use super::{components::*, resources::*};
+
+#[allow(clippy::type_complexity)]
+pub fn attack_target(
+    mut commands: Commands,
+    clock: Res<SimulationClock>,
+    mut attackers: Query<(
+        Entity,
+        &Transform,
+        &UnitAttack,
+        &AttackTarget,
+        Option<&mut AttackCooldown>,
+        Option<&HoldPosition>,
+    )>,
+    targets: Query<&Transform, With<Health>>,
+    mut damage: EventWriter<DamageEvent>,
+) {
+    let dt = clock.delta_seconds();
+
+    for (entity, transform, attack, target, cooldown, holding) in attackers.iter_mut() {
+        // Target is gone (dead or despawned) so the order is done.
+        let Ok(target_transform) = targets.get(target.0) else {
+            commands
+                .entity(entity)
+                .remove::<(AttackTarget, WalkToLocation)>();
+            continue;
+        };
+
+        if transform.translation.distance(target_transform.translation) > attack.range {
+            // Holding position, wait for it to come back in range
+            if holding.is_some() {
+                continue;
+            }
+            // Chase it, the walk order gets refreshed every tick so we follow it as it moves.
+            commands
+                .entity(entity)
+                .insert(WalkToLocation(target_transform.translation));
+            continue;
+        }
+
+        commands.entity(entity).remove::<WalkToLocation>();
+
+        let Some(mut cooldown) = cooldown else {
+            commands.entity(entity).insert(AttackCooldown::default());
+            continue;
+        };
+
+        cooldown.0 -= dt;
+        if cooldown.0 <= 0.0 {
+            cooldown.0 = attack.cooldown;
+            damage.send(DamageEvent {
+                target: target.0,
+                amount: attack.damage,
+            });
+        }
+    }
+}
+
+pub fn apply_damage(mut events: EventReader<DamageEvent>, mut health: Query<&mut Health>) {
+    for event in events.iter() {
+        if let Ok(mut health) = health.get_mut(event.target) {
+            health.current -= event.amount;
+        }
+    }
+}
+
+pub fn despawn_dead(mut commands: Commands, query: Query<(Entity, &Health, Option<&Name>)>) {
+    for (entity, health, name) in query.iter() {
+        if health.current <= 0.0 {
+            if let Some(name) = name {
+                info!("{name} died");
+            }
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
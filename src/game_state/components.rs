@@ -0,0 +1,31 @@
+use bevy::prelude::*;
+
+#[derive(Component)]
+pub struct MainMenuScreen;
+
+/// Picks the scenario at this index of `SCENARIOS`.
+#[derive(Component)]
+pub struct ScenarioButton(pub usize);
+
+#[derive(Component)]
+pub struct LoadingScreen;
+
+#[derive(Component)]
+pub struct LoadingText;
+
+#[derive(Component)]
+pub struct PauseScreen;
+
+#[derive(Component)]
+pub struct GameOverScreen;
+
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MenuButton {
+    /// Load the selected scenario and play it.
+    Play,
+    Resume,
+    /// Start the scenario over from its seed.
+    Restart,
+    MainMenu,
+    Quit,
+}
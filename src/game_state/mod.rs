@@ -0,0 +1,65 @@
+use bevy::prelude::*;
+
+pub mod components;
+pub mod resources;
+mod systems;
+
+use crate::{hud::resources::HudFont, intent::InputStage};
+use components::*;
+use resources::*;
+use systems::*;
+
+/// Where the game is up to. The simulation only runs `InGame`, every other state holds it where
+/// it is.
+#[derive(States, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum GameState {
+    #[default]
+    MainMenu,
+    /// The scenario's world is spawned, waiting on the assets it needs before it can start.
+    Loading,
+    InGame,
+    /// The simulation is frozen but the camera still moves.
+    Paused,
+    /// One side has no units left.
+    GameOver,
+}
+
+/// Moves between states as the world loads and the game is won or lost. Enough on its own for
+/// the headless runner, `MenuPlugin` is the screens for each state.
+pub struct GameStatePlugin;
+
+impl Plugin for GameStatePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_state::<GameState>()
+            .init_resource::<LoadingAssets>()
+            .add_system(start_loading.in_schedule(OnEnter(GameState::Loading)))
+            .add_system(check_loading.run_if(in_state(GameState::Loading)))
+            .add_system(check_game_over.run_if(in_state(GameState::InGame)));
+    }
+}
+
+/// The main menu with scenario selection, the loading screen, pause menu (F10) and game over.
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HudFont>()
+            .add_system(spawn_main_menu.in_schedule(OnEnter(GameState::MainMenu)))
+            .add_system(despawn_screen::<MainMenuScreen>.in_schedule(OnExit(GameState::MainMenu)))
+            .add_system(
+                load_hud_font
+                    .after(start_loading)
+                    .in_schedule(OnEnter(GameState::Loading)),
+            )
+            .add_system(spawn_loading_screen.in_schedule(OnEnter(GameState::Loading)))
+            .add_system(despawn_screen::<LoadingScreen>.in_schedule(OnExit(GameState::Loading)))
+            .add_system(spawn_pause_screen.in_schedule(OnEnter(GameState::Paused)))
+            .add_system(despawn_screen::<PauseScreen>.in_schedule(OnExit(GameState::Paused)))
+            .add_system(spawn_game_over_screen.in_schedule(OnEnter(GameState::GameOver)))
+            .add_system(despawn_screen::<GameOverScreen>.in_schedule(OnExit(GameState::GameOver)))
+            .add_systems((scenario_button_click, menu_button_click).in_set(InputStage::Ui))
+            .add_system(pause_hotkeys)
+            .add_system(style_menu_buttons)
+            .add_system(update_loading_text.run_if(in_state(GameState::Loading)));
+    }
+}
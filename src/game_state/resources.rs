@@ -0,0 +1,34 @@
+use bevy::{asset::LoadState, prelude::*};
+
+use crate::ai::ENEMY_BEHAVIOUR_PATH;
+
+/// Opens the pause menu, Space only pauses the simulation.
+pub const MENU_KEY: KeyCode = KeyCode::F10;
+
+/// Loaded on the way into every game, it doesn't start until they're all in.
+pub const GAME_ASSETS: &[&str] = &[ENEMY_BEHAVIOUR_PATH];
+
+/// What the loading screen is waiting on.
+#[derive(Resource, Default)]
+pub struct LoadingAssets(pub Vec<HandleUntyped>);
+
+impl LoadingAssets {
+    /// How many of the assets have loaded, out of how many.
+    pub fn progress(&self, asset_server: &AssetServer) -> (usize, usize) {
+        let loaded = self
+            .0
+            .iter()
+            .filter(|handle| asset_server.get_load_state(*handle) == LoadState::Loaded)
+            .count();
+        (loaded, self.0.len())
+    }
+}
+
+/// How the last game ended.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameOutcome {
+    /// Every enemy is dead.
+    Victory,
+    /// Every player unit is dead.
+    Defeat,
+}
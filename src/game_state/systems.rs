@@ -0,0 +1,323 @@
+use bevy::{app::AppExit, asset::LoadState, prelude::*, ui::FocusPolicy};
+
+use crate::{
+    hud::resources::HudFont,
+    save::resources::GameSeed,
+    simulation::resources::{SimulationClock, SimulationTick},
+    units::components::{Enemy, Player},
+    world_gen::resources::{ActiveScenario, StartScenario, SCENARIOS},
+};
+
+use super::{components::*, resources::*, GameState};
+
+pub fn start_loading(asset_server: Res<AssetServer>, mut loading: ResMut<LoadingAssets>) {
+    loading.0 = GAME_ASSETS
+        .iter()
+        .map(|path| asset_server.load_untyped(*path))
+        .collect();
+}
+
+/// Start once everything's in, or give up and go back to the menu if anything won't load.
+pub fn check_loading(
+    asset_server: Res<AssetServer>,
+    loading: Res<LoadingAssets>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    match asset_server.get_group_load_state(loading.0.iter().map(|handle| handle.id())) {
+        LoadState::Loaded => next_state.set(GameState::InGame),
+        LoadState::Failed => {
+            for handle in loading.0.iter() {
+                if asset_server.get_load_state(handle) == LoadState::Failed {
+                    error!("Failed to load {:?}", asset_server.get_handle_path(handle));
+                }
+            }
+            next_state.set(GameState::MainMenu);
+        }
+        _ => {}
+    }
+}
+
+/// Over once either side has nobody left. Nothing's run before the first tick, so the scenario's
+/// units might not be there yet.
+pub fn check_game_over(
+    mut commands: Commands,
+    tick: Res<SimulationTick>,
+    players: Query<(), With<Player>>,
+    enemies: Query<(), With<Enemy>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if tick.0 == 0 {
+        return;
+    }
+
+    let outcome = if players.is_empty() {
+        GameOutcome::Defeat
+    } else if enemies.is_empty() {
+        GameOutcome::Victory
+    } else {
+        return;
+    };
+
+    info!("{outcome:?} at tick {}", tick.0);
+    commands.insert_resource(outcome);
+    next_state.set(GameState::GameOver);
+}
+
+/// The screens are no good without their font, so the game waits on it as well.
+pub fn load_hud_font(font: Res<HudFont>, mut loading: ResMut<LoadingAssets>) {
+    loading.0.push(font.0.clone_untyped());
+}
+
+const MENU_BACKGROUND: Color = Color::rgb(0.05, 0.05, 0.08);
+const MENU_BACKDROP: Color = Color::rgba(0.0, 0.0, 0.0, 0.6);
+const MENU_BUTTON: Color = Color::rgb(0.2, 0.2, 0.2);
+const MENU_BUTTON_HOVERED: Color = Color::rgb(0.3, 0.3, 0.3);
+const MENU_BUTTON_SELECTED: Color = Color::rgb(0.2, 0.4, 0.6);
+
+/// A full window screen with its contents in a column down the middle, on top of the HUD.
+/// Opaque screens also keep the mouse off the world, the others only block with their buttons
+/// so the camera still works around them.
+fn spawn_screen(
+    commands: &mut Commands,
+    name: &str,
+    background: Color,
+    opaque: bool,
+    contents: impl FnOnce(&mut ChildBuilder),
+) -> Entity {
+    let mut screen = commands.spawn(NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            size: Size::all(Val::Percent(100.0)),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            gap: Size::all(Val::Px(8.0)),
+            ..default()
+        },
+        background_color: background.into(),
+        focus_policy: if opaque {
+            FocusPolicy::Block
+        } else {
+            FocusPolicy::Pass
+        },
+        z_index: ZIndex::Global(10),
+        ..default()
+    });
+    if opaque {
+        screen.insert(Interaction::default());
+    }
+    screen
+        .insert(Name::new(name.to_string()))
+        .with_children(contents)
+        .id()
+}
+
+/// A button doing whatever `action` (a `MenuButton` or `ScenarioButton`) says, with a line of
+/// smaller text under its label if it needs explaining.
+fn spawn_button(
+    parent: &mut ChildBuilder,
+    font: &HudFont,
+    label: &str,
+    detail: Option<&str>,
+    action: impl Component,
+) {
+    parent
+        .spawn(ButtonBundle {
+            style: Style {
+                size: Size::new(Val::Px(260.0), Val::Auto),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                padding: UiRect::all(Val::Px(8.0)),
+                ..default()
+            },
+            background_color: MENU_BUTTON.into(),
+            ..default()
+        })
+        .insert(action)
+        .with_children(|button| {
+            button.spawn(TextBundle::from_section(label, font.style(18.0)));
+            if let Some(detail) = detail {
+                button.spawn(TextBundle::from_section(detail, font.style(12.0)));
+            }
+        });
+}
+
+pub fn spawn_main_menu(mut commands: Commands, font: Res<HudFont>, seed: Res<GameSeed>) {
+    let screen = spawn_screen(
+        &mut commands,
+        "Main Menu",
+        MENU_BACKGROUND,
+        true,
+        |screen| {
+            screen.spawn(TextBundle::from_section(
+                "Pick a scenario",
+                font.style(28.0),
+            ));
+            for (i, scenario) in SCENARIOS.iter().enumerate() {
+                spawn_button(
+                    screen,
+                    &font,
+                    scenario.name,
+                    Some(scenario.description),
+                    ScenarioButton(i),
+                );
+            }
+            screen.spawn(TextBundle::from_section(
+                format!("Seed {}", seed.0),
+                font.style(12.0),
+            ));
+            spawn_button(screen, &font, "Play", None, MenuButton::Play);
+            spawn_button(screen, &font, "Quit", None, MenuButton::Quit);
+        },
+    );
+    commands.entity(screen).insert(MainMenuScreen);
+}
+
+pub fn spawn_loading_screen(mut commands: Commands, font: Res<HudFont>) {
+    let screen = spawn_screen(&mut commands, "Loading", MENU_BACKGROUND, true, |screen| {
+        screen
+            .spawn(TextBundle::from_section("Loading", font.style(24.0)))
+            .insert(LoadingText);
+    });
+    commands.entity(screen).insert(LoadingScreen);
+}
+
+pub fn update_loading_text(
+    asset_server: Res<AssetServer>,
+    loading: Res<LoadingAssets>,
+    scenario: Res<ActiveScenario>,
+    mut text: Query<&mut Text, With<LoadingText>>,
+) {
+    let Ok(mut text) = text.get_single_mut() else { return; };
+
+    let (loaded, total) = loading.progress(&asset_server);
+    let value = format!("Loading {} ({loaded}/{total})", scenario.name);
+    if text.sections[0].value != value {
+        text.sections[0].value = value;
+    }
+}
+
+pub fn spawn_pause_screen(mut commands: Commands, font: Res<HudFont>) {
+    let screen = spawn_screen(
+        &mut commands,
+        "Pause Menu",
+        MENU_BACKDROP,
+        false,
+        |screen| {
+            screen.spawn(TextBundle::from_section("Paused", font.style(28.0)));
+            screen.spawn(TextBundle::from_section(
+                "F10 or Escape to carry on",
+                font.style(12.0),
+            ));
+            spawn_button(screen, &font, "Resume", None, MenuButton::Resume);
+            spawn_button(screen, &font, "Restart", None, MenuButton::Restart);
+            spawn_button(screen, &font, "Main menu", None, MenuButton::MainMenu);
+        },
+    );
+    commands.entity(screen).insert(PauseScreen);
+}
+
+pub fn spawn_game_over_screen(
+    mut commands: Commands,
+    font: Res<HudFont>,
+    outcome: Res<GameOutcome>,
+    clock: Res<SimulationClock>,
+) {
+    let title = match *outcome {
+        GameOutcome::Victory => "Victory",
+        GameOutcome::Defeat => "Defeat",
+    };
+    let screen = spawn_screen(&mut commands, "Game Over", MENU_BACKDROP, false, |screen| {
+        screen.spawn(TextBundle::from_section(title, font.style(32.0)));
+        screen.spawn(TextBundle::from_section(
+            format!("after {:.0}s", clock.elapsed_seconds()),
+            font.style(14.0),
+        ));
+        spawn_button(screen, &font, "Play again", None, MenuButton::Restart);
+        spawn_button(screen, &font, "Main menu", None, MenuButton::MainMenu);
+    });
+    commands.entity(screen).insert(GameOverScreen);
+}
+
+pub fn despawn_screen<T: Component>(mut commands: Commands, screens: Query<Entity, With<T>>) {
+    for screen in screens.iter() {
+        commands.entity(screen).despawn_recursive();
+    }
+}
+
+pub fn scenario_button_click(
+    buttons: Query<(&Interaction, &ScenarioButton), Changed<Interaction>>,
+    mut scenario: ResMut<ActiveScenario>,
+) {
+    for (interaction, button) in buttons.iter() {
+        let name = SCENARIOS[button.0].name;
+        // Only touch it when it changes, the camera reloads its bookmarks whenever it does
+        if *interaction == Interaction::Clicked && scenario.name != name {
+            scenario.name = name.to_string();
+        }
+    }
+}
+
+pub fn menu_button_click(
+    buttons: Query<(&Interaction, &MenuButton), Changed<Interaction>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut scenario: EventWriter<StartScenario>,
+    mut exit: EventWriter<AppExit>,
+) {
+    for (interaction, button) in buttons.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+
+        match button {
+            MenuButton::Play => next_state.set(GameState::Loading),
+            MenuButton::Resume => next_state.set(GameState::InGame),
+            MenuButton::Restart => {
+                // Everything it needs is already loaded
+                scenario.send(StartScenario);
+                next_state.set(GameState::InGame);
+            }
+            MenuButton::MainMenu => next_state.set(GameState::MainMenu),
+            MenuButton::Quit => exit.send(AppExit),
+        }
+    }
+}
+
+pub fn pause_hotkeys(
+    input_keys: Res<Input<KeyCode>>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    match state.0 {
+        GameState::InGame if input_keys.just_pressed(MENU_KEY) => next_state.set(GameState::Paused),
+        GameState::Paused if input_keys.any_just_pressed([MENU_KEY, KeyCode::Escape]) => {
+            next_state.set(GameState::InGame)
+        }
+        _ => {}
+    }
+}
+
+/// Highlight whatever the cursor's over, and which scenario will be played.
+#[allow(clippy::type_complexity)]
+pub fn style_menu_buttons(
+    scenario: Res<ActiveScenario>,
+    mut buttons: Query<
+        (&Interaction, Option<&ScenarioButton>, &mut BackgroundColor),
+        Or<(With<MenuButton>, With<ScenarioButton>)>,
+    >,
+) {
+    for (interaction, scenario_button, mut background) in buttons.iter_mut() {
+        let selected =
+            scenario_button.is_some_and(|button| SCENARIOS[button.0].name == scenario.name);
+        let color = if selected {
+            MENU_BUTTON_SELECTED
+        } else if *interaction != Interaction::None {
+            MENU_BUTTON_HOVERED
+        } else {
+            MENU_BUTTON
+        };
+        if background.0 != color {
+            background.0 = color;
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use std::time::{Duration, Instant};
+
+use bevy::{
+    input::InputPlugin, log::LogPlugin, prelude::*, scene::ScenePlugin, time::TimeUpdateStrategy,
+};
+
+use crate::{
+    add_game_world,
+    camera::resources::{CameraJump, CameraRigs},
+    cli::{CliOptions, DEFAULT_HEADLESS_TICKS},
+    combat::components::Health,
+    game_state::{resources::GameOutcome, GameState},
+    orders::OrderSimulationPlugin,
+    replay::{
+        resources::{PlayReplay, Playback},
+        CommandPlugin,
+    },
+    save::{
+        resources::{GameSeed, LoadGame, SaveGame, SaveResults},
+        SavePlugin,
+    },
+    selection::resources::Hovered,
+    simulation::{
+        resources::{SimulationRate, SimulationTick},
+        SIMULATION_TICK,
+    },
+    unit_render::{
+        components::UnitArchetype,
+        resources::{UnitRenderCache, UnitRenderSettings},
+    },
+    world_gen::resources::ActiveScenario,
+};
+
+/// Give up if the scenario still hasn't loaded after this long.
+const LOAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Run the simulation without a window as fast as it'll go, print how it went and hand back the
+/// exit code. Every update is exactly one tick so the same options always give the same result.
+pub fn run(options: &CliOptions, seed: u64, scenario: ActiveScenario) -> i32 {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(LogPlugin::default())
+        .add_plugin(AssetPlugin::default())
+        .add_plugin(TransformPlugin)
+        .add_plugin(HierarchyPlugin)
+        .add_plugin(InputPlugin)
+        .add_plugin(ScenePlugin)
+        // Units still get meshes and materials, there's just nothing to draw them
+        .add_asset::<Mesh>()
+        .add_asset::<StandardMaterial>()
+        .init_resource::<UnitRenderSettings>()
+        .init_resource::<UnitRenderCache>()
+        .init_resource::<Hovered>()
+        .init_resource::<CameraRigs>()
+        .add_event::<CameraJump>();
+
+    add_game_world(&mut app, seed, scenario);
+
+    app.add_plugin(OrderSimulationPlugin)
+        .add_plugin(SavePlugin)
+        .add_plugin(CommandPlugin);
+
+    // Straight past the menu. Nothing ticks until it's finished loading, otherwise how long the
+    // enemies stand around doing nothing depends on how fast the disk is
+    app.world
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::Loading);
+    let mut clock = Instant::now();
+    let started_loading = Instant::now();
+    loop {
+        step(&mut app, &mut clock);
+        match app.world.resource::<State<GameState>>().0 {
+            GameState::InGame => break,
+            // It goes back to the menu when something fails to load
+            GameState::MainMenu => return 1,
+            _ if started_loading.elapsed() > LOAD_TIMEOUT => {
+                error!("Gave up waiting for the scenario to load");
+                return 1;
+            }
+            _ => std::thread::sleep(Duration::from_millis(1)),
+        }
+    }
+
+    if let Some(path) = &options.load {
+        app.world.send_event(LoadGame { path: path.clone() });
+        step(&mut app, &mut clock);
+        // Carrying on from the seed instead would look like it worked
+        if app.world.resource::<SaveResults>().loaded != Some(true) {
+            error!("Couldn't load save {path:?}");
+            return 1;
+        }
+    }
+    if let Some(path) = &options.replay {
+        app.world.send_event(PlayReplay { path: path.clone() });
+        step(&mut app, &mut clock);
+        if !app.world.contains_resource::<Playback>() {
+            error!("Couldn't play replay {path:?}");
+            return 1;
+        }
+    }
+
+    let start_tick = app.world.resource::<SimulationTick>().0;
+    let mut end_tick = start_tick + options.ticks.unwrap_or(DEFAULT_HEADLESS_TICKS);
+    if let Some(playback) = app.world.get_resource::<Playback>() {
+        // The replay pauses itself at its end, there's nothing after it to run
+        end_tick = match options.ticks {
+            Some(_) => end_tick.min(playback.replay.end_tick),
+            None => playback.replay.end_tick,
+        };
+    }
+
+    let started = Instant::now();
+    // Nothing ticks once the game's over either
+    while app.world.resource::<SimulationTick>().0 < end_tick
+        && app.world.resource::<State<GameState>>().0 == GameState::InGame
+    {
+        step(&mut app, &mut clock);
+    }
+    let elapsed = started.elapsed();
+    app.world.resource_mut::<SimulationRate>().paused = true;
+
+    let ticks = app.world.resource::<SimulationTick>().0 - start_tick;
+    print_report(&mut app.world, ticks, elapsed);
+
+    if let Some(path) = &options.dump {
+        app.world.send_event(SaveGame { path: path.clone() });
+        step(&mut app, &mut clock);
+        if app.world.resource::<SaveResults>().saved != Some(true) {
+            error!("Couldn't dump the game to {path:?}");
+            return 1;
+        }
+    }
+
+    0
+}
+
+/// Update once with exactly a tick's worth of time passing, however long it really took.
+fn step(app: &mut App, clock: &mut Instant) {
+    *clock += Duration::from_secs_f32(SIMULATION_TICK);
+    app.world
+        .insert_resource(TimeUpdateStrategy::ManualInstant(*clock));
+    app.update();
+}
+
+fn print_report(world: &mut World, ticks: u64, elapsed: Duration) {
+    // Loading a save or replay brings its own seed
+    let seed = world.resource::<GameSeed>().0;
+    let tick = world.resource::<SimulationTick>().0;
+    let seconds = elapsed.as_secs_f64();
+    println!(
+        "seed {seed}: ran {ticks} ticks ({:.1}s of game time) to tick {tick} in {seconds:.2}s, {:.0} ticks/s",
+        ticks as f32 * SIMULATION_TICK,
+        ticks as f64 / seconds.max(f64::EPSILON),
+    );
+
+    let mut units = world.query::<(&UnitArchetype, Option<&Health>)>();
+    for archetype in [UnitArchetype::Player, UnitArchetype::Enemy] {
+        let (alive, health) = units
+            .iter(world)
+            .filter(|(unit, _)| **unit == archetype)
+            .fold((0, 0.0), |(alive, total), (_, health)| {
+                (
+                    alive + 1,
+                    total + health.map_or(0.0, |health| health.current),
+                )
+            });
+        println!("{archetype:?}: {alive} alive, {health:.0} health");
+    }
+
+    if let Some(outcome) = world.get_resource::<GameOutcome>() {
+        println!("Game over: {outcome:?}");
+    }
+}
@@ -0,0 +1,57 @@
+use bevy::prelude::*;
+
+use crate::orders::resources::OrderKind;
+
+/// Bottom left panel describing the current selection.
+#[derive(Component)]
+pub struct InspectionPanel;
+
+#[derive(Component)]
+pub struct InspectionText;
+
+/// Holds a portrait per selected unit when more than one is selected.
+#[derive(Component)]
+pub struct SelectionGrid;
+
+/// Clicking it narrows the selection down to just this unit.
+#[derive(Component)]
+pub struct SelectionPortrait(pub Entity);
+
+/// The health label on a portrait.
+#[derive(Component)]
+pub struct PortraitHealth(pub Entity);
+
+/// Top middle, the game speed with buttons to change it.
+#[derive(Component)]
+pub struct SpeedPanel;
+
+#[derive(Component)]
+pub struct SpeedText;
+
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpeedButton {
+    Slower,
+    Pause,
+    Faster,
+}
+
+#[derive(Component)]
+pub struct Tooltip;
+
+#[derive(Component)]
+pub struct TooltipText;
+
+/// Bottom middle panel with a button per order the selection can be given.
+#[derive(Component)]
+pub struct CommandCard;
+
+/// Where the order buttons go, rebuilt whenever the order registry changes.
+#[derive(Component)]
+pub struct CommandButtons;
+
+#[derive(Component)]
+pub struct CommandButton(pub OrderKind);
+
+/// Describes the hovered order, or what the pending order is waiting for.
+#[derive(Component)]
+pub struct CommandTooltipText;
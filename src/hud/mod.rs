@@ -0,0 +1,35 @@
+use bevy::prelude::*;
+
+pub mod components;
+pub mod resources;
+mod systems;
+
+use crate::{game_state::GameState, intent::InputStage};
+use resources::*;
+use systems::*;
+
+/// In-game UI, the unit inspection panel, hover tooltips, the command card and game speed.
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HudFont>()
+            .init_resource::<InspectedUnits>()
+            .add_startup_system(spawn_hud)
+            .add_startup_system(spawn_command_card)
+            .add_startup_system(spawn_speed_panel)
+            .add_systems(
+                (portrait_click, command_card_click, speed_button_click).in_set(InputStage::Ui),
+            )
+            .add_system(speed_hotkeys.run_if(in_state(GameState::InGame)))
+            .add_systems((
+                update_inspection_text,
+                rebuild_selection_grid,
+                update_portrait_health,
+                update_tooltip,
+                rebuild_command_card,
+                update_command_card,
+                update_speed_panel,
+            ));
+    }
+}
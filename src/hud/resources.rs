@@ -0,0 +1,31 @@
+use bevy::prelude::*;
+
+pub const HUD_FONT_PATH: &str = "fonts/DejaVuSans.ttf";
+
+/// Game speed controls, orders can still be given while paused and go out on the next tick.
+pub const PAUSE_KEY: KeyCode = KeyCode::Space;
+pub const SLOWER_KEY: KeyCode = KeyCode::LBracket;
+pub const FASTER_KEY: KeyCode = KeyCode::RBracket;
+
+#[derive(Resource)]
+pub struct HudFont(pub Handle<Font>);
+
+impl FromWorld for HudFont {
+    fn from_world(world: &mut World) -> Self {
+        HudFont(world.resource::<AssetServer>().load(HUD_FONT_PATH))
+    }
+}
+
+impl HudFont {
+    pub fn style(&self, font_size: f32) -> TextStyle {
+        TextStyle {
+            font: self.0.clone(),
+            font_size,
+            color: Color::WHITE,
+        }
+    }
+}
+
+/// The units the selection grid was last built for, so it's only rebuilt when the selection changes.
+#[derive(Resource, Default)]
+pub struct InspectedUnits(pub Vec<Entity>);
@@ -0,0 +1,590 @@
+use std::fmt::Write;
+
+use bevy::{prelude::*, ui::FocusPolicy, window::PrimaryWindow};
+
+use crate::{
+    combat::components::{AttackTarget, Health},
+    orders::resources::{IssueOrder, OrderRegistry, PendingOrder},
+    replay::resources::{PendingCommands, PlayerCommand},
+    selection::{
+        components::{Relationship, SelectedUnit},
+        resources::Hovered,
+    },
+    simulation::resources::SimulationRate,
+    units::components::{Enemy, Player, UnitMovement, UnitSize, UnitView, WalkToLocation},
+};
+
+use super::{components::*, resources::*};
+
+/// Everything the panel and tooltip show about a unit.
+type UnitInfo<'a> = (
+    Option<&'a Name>,
+    Option<&'a Health>,
+    Option<&'a UnitMovement>,
+    Option<&'a UnitView>,
+    Option<&'a UnitSize>,
+    Option<&'a WalkToLocation>,
+    Option<&'a AttackTarget>,
+    Option<&'a Player>,
+    Option<&'a Enemy>,
+);
+
+pub fn spawn_hud(mut commands: Commands, font: Res<HudFont>) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    left: Val::Px(10.0),
+                    bottom: Val::Px(10.0),
+                    ..default()
+                },
+                size: Size::new(Val::Px(320.0), Val::Auto),
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(8.0)),
+                gap: Size::all(Val::Px(6.0)),
+                ..default()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.8).into(),
+            visibility: Visibility::Hidden,
+            // Clicking on the panel shouldn't click through to the world
+            focus_policy: FocusPolicy::Block,
+            ..default()
+        })
+        .insert(Interaction::default())
+        .insert(InspectionPanel)
+        .insert(Name::new("Inspection Panel"))
+        .with_children(|panel| {
+            panel
+                .spawn(TextBundle::from_section("", font.style(14.0)))
+                .insert(InspectionText);
+            panel
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_wrap: FlexWrap::Wrap,
+                        gap: Size::all(Val::Px(4.0)),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .insert(SelectionGrid);
+        });
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                padding: UiRect::all(Val::Px(4.0)),
+                ..default()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.7).into(),
+            visibility: Visibility::Hidden,
+            ..default()
+        })
+        .insert(Tooltip)
+        .insert(Name::new("Tooltip"))
+        .with_children(|tooltip| {
+            tooltip
+                .spawn(TextBundle::from_section("", font.style(12.0)))
+                .insert(TooltipText);
+        });
+}
+
+/// Details of a single selected unit, or a count when there's more than one.
+pub fn update_inspection_text(
+    selected: Query<Entity, With<SelectedUnit>>,
+    units: Query<UnitInfo>,
+    mut panel: Query<&mut Visibility, With<InspectionPanel>>,
+    mut text: Query<&mut Text, With<InspectionText>>,
+) {
+    let Ok(mut visibility) = panel.get_single_mut() else { return; };
+    let Ok(mut text) = text.get_single_mut() else { return; };
+
+    let count = selected.iter().count();
+    let new_visibility = if count == 0 {
+        Visibility::Hidden
+    } else {
+        Visibility::Inherited
+    };
+    if *visibility != new_visibility {
+        *visibility = new_visibility;
+    }
+
+    let value = match selected.iter().next() {
+        Some(unit) if count == 1 => units.get(unit).map(describe_unit).unwrap_or_default(),
+        _ => format!("{count} units selected"),
+    };
+    if text.sections[0].value != value {
+        text.sections[0].value = value;
+    }
+}
+
+/// One portrait per selected unit when several are selected, only rebuilt when the selection changes.
+#[allow(clippy::type_complexity)]
+pub fn rebuild_selection_grid(
+    mut commands: Commands,
+    font: Res<HudFont>,
+    mut inspected: ResMut<InspectedUnits>,
+    selected: Query<(Entity, Option<&Player>, Option<&Enemy>), With<SelectedUnit>>,
+    grid: Query<Entity, With<SelectionGrid>>,
+) {
+    let mut units: Vec<_> = selected.iter().collect();
+    units.sort_by_key(|(entity, _, _)| *entity);
+    let entities: Vec<Entity> = units.iter().map(|(entity, _, _)| *entity).collect();
+    if entities == inspected.0 {
+        return;
+    }
+    inspected.0 = entities;
+
+    let Ok(grid) = grid.get_single() else { return; };
+    let mut grid = commands.entity(grid);
+    grid.despawn_descendants();
+    // A single unit is described by the text instead
+    if units.len() < 2 {
+        return;
+    }
+
+    grid.with_children(|grid| {
+        for (unit, player, enemy) in units {
+            grid.spawn(ButtonBundle {
+                style: Style {
+                    size: Size::all(Val::Px(36.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: team_color(Relationship::of(player.is_some(), enemy.is_some()))
+                    .into(),
+                ..default()
+            })
+            .insert(SelectionPortrait(unit))
+            .with_children(|portrait| {
+                portrait
+                    .spawn(TextBundle::from_section("", font.style(11.0)))
+                    .insert(PortraitHealth(unit));
+            });
+        }
+    });
+}
+
+pub fn update_portrait_health(
+    health: Query<&Health>,
+    mut labels: Query<(&PortraitHealth, &mut Text)>,
+) {
+    for (label, mut text) in labels.iter_mut() {
+        let value = match health.get(label.0) {
+            Ok(health) => format!("{:.0}%", health.current / health.max * 100.0),
+            Err(_) => String::new(),
+        };
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+}
+
+/// Clicking a portrait deselects everything else.
+pub fn portrait_click(
+    mut commands: Commands,
+    portraits: Query<(&Interaction, &SelectionPortrait), Changed<Interaction>>,
+    selected: Query<(Entity, &SelectedUnit)>,
+) {
+    for (interaction, portrait) in portraits.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+
+        for (unit, selection) in selected.iter() {
+            if unit != portrait.0 {
+                selection.clear(&mut commands, unit);
+            }
+        }
+    }
+}
+
+/// Name and health next to the cursor for whatever unit it's over.
+pub fn update_tooltip(
+    hovered: Res<Hovered>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    units: Query<UnitInfo>,
+    mut tooltip: Query<(&mut Style, &mut Visibility), With<Tooltip>>,
+    mut text: Query<&mut Text, With<TooltipText>>,
+) {
+    let Ok((mut style, mut visibility)) = tooltip.get_single_mut() else { return; };
+    let Ok(mut text) = text.get_single_mut() else { return; };
+
+    let cursor = windows
+        .get_single()
+        .ok()
+        .and_then(|window| Some((window.cursor_position()?, window.height())));
+    let info = hovered.entity.and_then(|unit| units.get(unit).ok());
+    let (Some((cursor, height)), Some((name, health, .., player, enemy))) = (cursor, info) else {
+        if *visibility != Visibility::Hidden {
+            *visibility = Visibility::Hidden;
+        }
+        return;
+    };
+
+    let mut value = unit_title(name, player.is_some(), enemy.is_some());
+    if let Some(health) = health {
+        let _ = write!(value, "\nHealth {:.0}/{:.0}", health.current, health.max);
+    }
+    if text.sections[0].value != value {
+        text.sections[0].value = value;
+    }
+
+    // Cursor position starts at the bottom of the window, UI starts at the top
+    style.position = UiRect {
+        left: Val::Px(cursor.x + 16.0),
+        top: Val::Px(height - cursor.y + 16.0),
+        ..default()
+    };
+    *visibility = Visibility::Inherited;
+}
+
+fn unit_title(name: Option<&Name>, player: bool, enemy: bool) -> String {
+    let name = name.map(|name| name.as_str()).unwrap_or("Unit");
+    format!("{name} ({:?})", Relationship::of(player, enemy))
+}
+
+fn describe_unit(
+    (name, health, movement, view, size, walk_to, attacking, player, enemy): UnitInfo,
+) -> String {
+    let mut value = unit_title(name, player.is_some(), enemy.is_some());
+
+    if let Some(health) = health {
+        let _ = write!(value, "\nHealth {:.0}/{:.0}", health.current, health.max);
+    }
+
+    let order = match (attacking, walk_to) {
+        (Some(_), _) => "Attacking".to_string(),
+        (_, Some(walk_to)) => format!("Moving to ({:.1}, {:.1})", walk_to.0.x, walk_to.0.z),
+        _ => "Idle".to_string(),
+    };
+    let _ = write!(value, "\nOrder: {order}");
+
+    if let Some(movement) = movement {
+        let _ = write!(
+            value,
+            "\nMove speed {:.1}, turn speed {:.1}",
+            movement.move_speed, movement.turn_speed
+        );
+    }
+    if let Some(view) = view {
+        let _ = write!(value, "\nView {}° out to {:.1}", view.fov, view.distance);
+    }
+    if let Some(size) = size {
+        let _ = write!(
+            value,
+            "\nSize {:.2} (collider {:.3})",
+            size.model, size.collider
+        );
+    }
+
+    value
+}
+
+fn team_color(relationship: Relationship) -> Color {
+    match relationship {
+        Relationship::Friendly => Color::rgb(0.1, 0.5, 0.15),
+        Relationship::Hostile => Color::rgb(0.55, 0.1, 0.1),
+        Relationship::Neutral => Color::rgb(0.5, 0.45, 0.1),
+    }
+}
+
+pub fn spawn_command_card(mut commands: Commands, font: Res<HudFont>) {
+    // Full width row just to centre the card, it has no Interaction so it doesn't block clicks
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    bottom: Val::Px(10.0),
+                    ..default()
+                },
+                size: Size::new(Val::Percent(100.0), Val::Auto),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(Name::new("Command Card"))
+        .with_children(|row| {
+            row.spawn(NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    padding: UiRect::all(Val::Px(6.0)),
+                    gap: Size::all(Val::Px(4.0)),
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.8).into(),
+                visibility: Visibility::Hidden,
+                focus_policy: FocusPolicy::Block,
+                ..default()
+            })
+            .insert(Interaction::default())
+            .insert(CommandCard)
+            .with_children(|card| {
+                card.spawn(TextBundle::from_section("", font.style(12.0)))
+                    .insert(CommandTooltipText);
+                card.spawn(NodeBundle {
+                    style: Style {
+                        gap: Size::all(Val::Px(4.0)),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .insert(CommandButtons);
+            });
+        });
+}
+
+/// A button per registered order, so anything added to the registry shows up on its own.
+pub fn rebuild_command_card(
+    mut commands: Commands,
+    font: Res<HudFont>,
+    registry: Res<OrderRegistry>,
+    buttons: Query<Entity, With<CommandButtons>>,
+) {
+    if !registry.is_changed() {
+        return;
+    }
+    let Ok(buttons) = buttons.get_single() else { return; };
+
+    let mut buttons = commands.entity(buttons);
+    buttons.despawn_descendants();
+    buttons.with_children(|buttons| {
+        for definition in registry.orders.iter() {
+            buttons
+                .spawn(ButtonBundle {
+                    style: Style {
+                        size: Size::new(Val::Px(64.0), Val::Px(48.0)),
+                        flex_direction: FlexDirection::Column,
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: COMMAND_BUTTON.into(),
+                    ..default()
+                })
+                .insert(CommandButton(definition.kind))
+                .with_children(|button| {
+                    button.spawn(TextBundle::from_section(definition.label, font.style(14.0)));
+                    button.spawn(TextBundle::from_section(
+                        format!("{:?}", definition.hotkey),
+                        font.style(10.0),
+                    ));
+                });
+        }
+    });
+}
+
+const COMMAND_BUTTON: Color = Color::rgb(0.2, 0.2, 0.2);
+const COMMAND_BUTTON_HOVERED: Color = Color::rgb(0.3, 0.3, 0.3);
+const COMMAND_BUTTON_PENDING: Color = Color::rgb(0.2, 0.4, 0.6);
+
+/// Only show the card with something selected, highlight the order waiting for a target and
+/// describe whichever order is under the cursor.
+pub fn update_command_card(
+    registry: Res<OrderRegistry>,
+    pending: Res<PendingOrder>,
+    selected: Query<(), With<SelectedUnit>>,
+    mut card: Query<&mut Visibility, With<CommandCard>>,
+    mut buttons: Query<(&CommandButton, &Interaction, &mut BackgroundColor)>,
+    mut tooltip: Query<&mut Text, With<CommandTooltipText>>,
+) {
+    let Ok(mut visibility) = card.get_single_mut() else { return; };
+    let Ok(mut tooltip) = tooltip.get_single_mut() else { return; };
+
+    let new_visibility = if selected.is_empty() {
+        Visibility::Hidden
+    } else {
+        Visibility::Inherited
+    };
+    if *visibility != new_visibility {
+        *visibility = new_visibility;
+    }
+
+    let mut hovered = None;
+    for (button, interaction, mut background) in buttons.iter_mut() {
+        let color = if pending.0 == Some(button.0) {
+            COMMAND_BUTTON_PENDING
+        } else if *interaction != Interaction::None {
+            COMMAND_BUTTON_HOVERED
+        } else {
+            COMMAND_BUTTON
+        };
+        if background.0 != color {
+            background.0 = color;
+        }
+        if *interaction != Interaction::None {
+            hovered = Some(button.0);
+        }
+    }
+
+    let value = match (hovered, pending.0) {
+        (Some(kind), _) => registry
+            .get(kind)
+            .map(|definition| {
+                format!(
+                    "{} ({:?}): {}",
+                    definition.label, definition.hotkey, definition.tooltip
+                )
+            })
+            .unwrap_or_default(),
+        (None, Some(kind)) => registry
+            .get(kind)
+            .map(|definition| format!("{}: pick a target, right click to cancel", definition.label))
+            .unwrap_or_default(),
+        (None, None) => String::new(),
+    };
+    if tooltip.sections[0].value != value {
+        tooltip.sections[0].value = value;
+    }
+}
+
+pub fn command_card_click(
+    registry: Res<OrderRegistry>,
+    mut pending: ResMut<PendingOrder>,
+    mut orders: EventWriter<IssueOrder>,
+    buttons: Query<(&Interaction, &CommandButton), Changed<Interaction>>,
+) {
+    for (interaction, button) in buttons.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        if let Some(definition) = registry.get(button.0) {
+            definition.activate(&mut pending, &mut orders);
+        }
+    }
+}
+
+pub fn spawn_speed_panel(mut commands: Commands, font: Res<HudFont>) {
+    // Full width row just to centre the panel, same as the command card
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Px(10.0),
+                    ..default()
+                },
+                size: Size::new(Val::Percent(100.0), Val::Auto),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(Name::new("Speed Panel"))
+        .with_children(|row| {
+            row.spawn(NodeBundle {
+                style: Style {
+                    align_items: AlignItems::Center,
+                    padding: UiRect::all(Val::Px(4.0)),
+                    gap: Size::all(Val::Px(4.0)),
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.8).into(),
+                focus_policy: FocusPolicy::Block,
+                ..default()
+            })
+            .insert(Interaction::default())
+            .insert(SpeedPanel)
+            .with_children(|panel| {
+                for (button, label) in [
+                    (SpeedButton::Slower, "-"),
+                    (SpeedButton::Pause, "||"),
+                    (SpeedButton::Faster, "+"),
+                ] {
+                    panel
+                        .spawn(ButtonBundle {
+                            style: Style {
+                                size: Size::all(Val::Px(24.0)),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            background_color: COMMAND_BUTTON.into(),
+                            ..default()
+                        })
+                        .insert(button)
+                        .with_children(|button| {
+                            button.spawn(TextBundle::from_section(label, font.style(14.0)));
+                        });
+                }
+                panel
+                    .spawn(TextBundle::from_section("", font.style(14.0)))
+                    .insert(SpeedText);
+            });
+        });
+}
+
+pub fn speed_hotkeys(input_keys: Res<Input<KeyCode>>, mut rate: ResMut<SimulationRate>) {
+    if input_keys.just_pressed(PAUSE_KEY) {
+        rate.paused = !rate.paused;
+    }
+    if input_keys.just_pressed(SLOWER_KEY) {
+        rate.slower();
+    }
+    if input_keys.just_pressed(FASTER_KEY) {
+        rate.faster();
+    }
+}
+
+pub fn speed_button_click(
+    buttons: Query<(&Interaction, &SpeedButton), Changed<Interaction>>,
+    mut rate: ResMut<SimulationRate>,
+) {
+    for (interaction, button) in buttons.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+
+        match button {
+            SpeedButton::Slower => rate.slower(),
+            SpeedButton::Pause => rate.paused = !rate.paused,
+            SpeedButton::Faster => rate.faster(),
+        }
+    }
+}
+
+/// The current speed, and while paused how many orders are waiting to go out when it carries on.
+pub fn update_speed_panel(
+    rate: Res<SimulationRate>,
+    pending: Res<PendingCommands>,
+    mut buttons: Query<(&SpeedButton, &Interaction, &mut BackgroundColor)>,
+    mut text: Query<&mut Text, With<SpeedText>>,
+) {
+    let Ok(mut text) = text.get_single_mut() else { return; };
+
+    for (button, interaction, mut background) in buttons.iter_mut() {
+        let color = if *button == SpeedButton::Pause && rate.paused {
+            COMMAND_BUTTON_PENDING
+        } else if *interaction != Interaction::None {
+            COMMAND_BUTTON_HOVERED
+        } else {
+            COMMAND_BUTTON
+        };
+        if background.0 != color {
+            background.0 = color;
+        }
+    }
+
+    let value = if rate.paused {
+        let waiting = pending
+            .0
+            .iter()
+            .filter(|command| matches!(command, PlayerCommand::Order { .. }))
+            .count();
+        match waiting {
+            0 => "Paused".to_string(),
+            1 => "Paused, 1 order waiting".to_string(),
+            _ => format!("Paused, {waiting} orders waiting"),
+        }
+    } else {
+        format!("{}x", rate.speed)
+    };
+    if text.sections[0].value != value {
+        text.sections[0].value = value;
+    }
+}
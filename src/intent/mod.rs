@@ -0,0 +1,46 @@
+use bevy::{input::InputSystem, prelude::*};
+
+pub mod resources;
+mod systems;
+
+use resources::*;
+use systems::*;
+
+/// Order the frame's input handling runs in, UI gets first pick of the mouse and the world
+/// only sees whatever the UI didn't claim.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputStage {
+    /// UI that wants to act on the mouse itself (minimap, buttons).
+    Ui,
+    /// Claims the mouse buttons while the cursor is over any UI.
+    UiBlocking,
+    /// Orders waiting for a target take the click before selection sees it.
+    Targeting,
+    /// Selection, orders and the camera.
+    World,
+}
+
+/// Turns raw mouse buttons into clicks and drags before anything else in the frame looks at them.
+pub struct IntentPlugin;
+
+impl Plugin for IntentPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<IntentSettings>()
+            .init_resource::<PointerIntents>()
+            .add_system(
+                update_pointer_intents
+                    .in_base_set(CoreSet::PreUpdate)
+                    .after(InputSystem),
+            )
+            .configure_sets(
+                (
+                    InputStage::Ui,
+                    InputStage::UiBlocking,
+                    InputStage::Targeting,
+                    InputStage::World,
+                )
+                    .chain(),
+            )
+            .add_system(claim_pointer_over_ui.in_set(InputStage::UiBlocking));
+    }
+}
@@ -0,0 +1,82 @@
+use bevy::{prelude::*, utils::HashMap};
+
+/// When a press stops being a click and starts being a drag.
+#[derive(Resource)]
+pub struct IntentSettings {
+    /// How far (in pixels) the mouse can move while held and still count as a click.
+    pub drag_threshold_pixels: f32,
+    /// How long (in seconds) a button can be held and still count as a click.
+    pub drag_threshold_seconds: f32,
+}
+
+impl Default for IntentSettings {
+    fn default() -> Self {
+        IntentSettings {
+            drag_threshold_pixels: 5.0,
+            drag_threshold_seconds: 0.3,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ButtonIntent {
+    /// When the button went down, in raw elapsed seconds.
+    pub pressed_at: f32,
+    /// How far the mouse has moved since the button went down.
+    pub moved: Vec2,
+    pub dragging: bool,
+    /// Released this frame without ever becoming a drag.
+    pub clicked: bool,
+    /// Released this frame after dragging.
+    pub drag_ended: bool,
+}
+
+/// Works out what the player meant by their mouse buttons (a click or a drag), so systems that
+/// share a button don't both act on it. A system can claim a button for the rest of the frame,
+/// after which everyone else sees nothing on it.
+#[derive(Resource, Default)]
+pub struct PointerIntents {
+    pub buttons: HashMap<MouseButton, ButtonIntent>,
+    pub claimed: Vec<MouseButton>,
+}
+
+impl PointerIntents {
+    fn get(&self, button: MouseButton) -> Option<&ButtonIntent> {
+        if self.is_claimed(button) {
+            return None;
+        }
+        self.buttons.get(&button)
+    }
+
+    pub fn is_claimed(&self, button: MouseButton) -> bool {
+        self.claimed.contains(&button)
+    }
+
+    /// Take the button for this frame, nobody after us will see it clicked or dragged.
+    pub fn claim(&mut self, button: MouseButton) {
+        if !self.is_claimed(button) {
+            self.claimed.push(button);
+        }
+    }
+
+    pub fn dragging(&self, button: MouseButton) -> bool {
+        self.get(button).map(|b| b.dragging).unwrap_or(false)
+    }
+
+    pub fn clicked(&self, button: MouseButton) -> bool {
+        self.get(button).map(|b| b.clicked).unwrap_or(false)
+    }
+
+    pub fn drag_ended(&self, button: MouseButton) -> bool {
+        self.get(button).map(|b| b.drag_ended).unwrap_or(false)
+    }
+
+    /// True if the button was clicked this frame and nobody else has it, also claims it.
+    pub fn consume_click(&mut self, button: MouseButton) -> bool {
+        let clicked = self.clicked(button);
+        if clicked {
+            self.claim(button);
+        }
+        clicked
+    }
+}
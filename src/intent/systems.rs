@@ -0,0 +1,63 @@
+use bevy::{input::mouse::MouseMotion, prelude::*};
+
+use super::resources::*;
+
+pub fn update_pointer_intents(
+    mut intents: ResMut<PointerIntents>,
+    mut ev_motion: EventReader<MouseMotion>,
+    mouse_btn: Res<Input<MouseButton>>,
+    settings: Res<IntentSettings>,
+    time: Res<Time>,
+) {
+    let now = time.raw_elapsed_seconds();
+    let motion: Vec2 = ev_motion.iter().map(|ev| ev.delta).sum();
+
+    // Claims only last the frame they were made in
+    intents.claimed.clear();
+
+    // Anything that finished last frame is done now
+    intents
+        .buttons
+        .retain(|_, intent| !intent.clicked && !intent.drag_ended);
+
+    for button in mouse_btn.get_just_pressed() {
+        intents.buttons.insert(
+            *button,
+            ButtonIntent {
+                pressed_at: now,
+                ..default()
+            },
+        );
+    }
+
+    for (button, intent) in intents.buttons.iter_mut() {
+        intent.moved += motion;
+        if !intent.dragging
+            && (intent.moved.length() > settings.drag_threshold_pixels
+                || now - intent.pressed_at > settings.drag_threshold_seconds)
+        {
+            intent.dragging = true;
+        }
+
+        if mouse_btn.just_released(*button) {
+            if intent.dragging {
+                intent.drag_ended = true;
+                intent.dragging = false;
+            } else {
+                intent.clicked = true;
+            }
+        }
+    }
+}
+
+/// Clicking on UI shouldn't also select or order whatever is behind it.
+pub fn claim_pointer_over_ui(mut intents: ResMut<PointerIntents>, ui: Query<&Interaction>) {
+    if ui
+        .iter()
+        .any(|interaction| *interaction != Interaction::None)
+    {
+        for button in [MouseButton::Left, MouseButton::Right, MouseButton::Middle] {
+            intents.claim(button);
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use bevy_turborand::prelude::RngPlugin;
+
+pub mod ai;
+pub mod camera;
+pub mod cli;
+pub mod combat;
+pub mod game_state;
+pub mod headless;
+pub mod hud;
+pub mod intent;
+pub mod line_drawing;
+pub mod minimap;
+pub mod orders;
+pub mod replay;
+pub mod save;
+pub mod selection;
+pub mod simulation;
+pub mod unit_render;
+pub mod units;
+pub mod vision;
+pub mod world_gen;
+
+use ai::AiPlugin;
+use combat::CombatPlugin;
+use game_state::GameStatePlugin;
+use save::resources::GameSeed;
+use simulation::SimulationPlugin;
+use units::UnitsPlugin;
+use world_gen::{resources::ActiveScenario, WorldGenPlugin};
+
+/// The world and everything that simulates it, without anything to look at or click on. Shared
+/// by the game and the headless runner.
+pub fn add_game_world(app: &mut App, seed: u64, scenario: ActiveScenario) {
+    app.insert_resource(scenario)
+        .insert_resource(GameSeed(seed))
+        // The simulation plugin runs physics on its fixed timestep instead
+        .add_plugin(RapierPhysicsPlugin::<NoUserData>::default().with_default_system_setup(false))
+        .add_plugin(RngPlugin::new().with_rng_seed(seed))
+        .add_plugin(GameStatePlugin)
+        .add_plugin(SimulationPlugin)
+        .add_plugin(WorldGenPlugin)
+        .add_plugin(UnitsPlugin)
+        .add_plugin(AiPlugin)
+        .add_plugin(CombatPlugin);
+}
@@ -1,17 +1,743 @@
-use bevy::{prelude::*, render::render_resource::PrimitiveTopology};
+use bevy::prelude::*;
 use bevy_polyline::prelude::*;
 
+// Shapes that turn into polylines. Unless a shape says otherwise it's flat on the X/Z plane at
+// the height of its centre/origin, angles go around the Y axis starting at +X and turning
+// towards -Z (anticlockwise looking down from above).
+
 pub struct Square(pub Vec3);
 
 impl From<Square> for Polyline {
     fn from(value: Square) -> Self {
         Polyline {
             vertices: vec![
-                Vec3::ZERO, 
+                Vec3::ZERO,
                 Vec3::new(0.0, 0.0, value.0.z),
-                value.0, 
-                Vec3::new(value.0.x, 0.0, 0.0), 
-                Vec3::ZERO ]
+                value.0,
+                Vec3::new(value.0.x, 0.0, 0.0),
+                Vec3::ZERO,
+            ],
+        }
+    }
+}
+
+/// A line through each point in turn, optionally with an arrow head on the end of the last leg.
+pub struct Path {
+    pub points: Vec<Vec3>,
+    pub arrow_head: Option<f32>,
+}
+
+impl From<Path> for Polyline {
+    fn from(value: Path) -> Self {
+        let mut vertices = value.points;
+
+        if let (Some(size), [.., from, to]) = (value.arrow_head, vertices.as_slice()) {
+            let (from, to) = (*from, *to);
+            vertices.extend(arrow_head(from, to, size));
+        }
+
+        Polyline { vertices }
+    }
+}
+
+/// A straight arrow from one point to another, the head is `head_size` long.
+pub struct Arrow {
+    pub from: Vec3,
+    pub to: Vec3,
+    pub head_size: f32,
+}
+
+impl From<Arrow> for Polyline {
+    fn from(value: Arrow) -> Self {
+        Path {
+            points: vec![value.from, value.to],
+            arrow_head: Some(value.head_size),
+        }
+        .into()
+    }
+}
+
+/// The two barbs of an arrow head flat on the ground, out to one barb, back to the tip and out to
+/// the other so the line carries straight on from the end of the shaft.
+fn arrow_head(from: Vec3, to: Vec3, size: f32) -> [Vec3; 3] {
+    let back = (from - to).normalize_or_zero() * size;
+    let side = back.cross(Vec3::Y).normalize_or_zero() * size * 0.5;
+
+    [to + back + side, to, to + back - side]
+}
+
+pub struct Circle {
+    pub center: Vec3,
+    pub radius: f32,
+    pub segments: usize,
+}
+
+impl From<Circle> for Polyline {
+    fn from(value: Circle) -> Self {
+        Arc {
+            center: value.center,
+            radius: value.radius,
+            start_angle: 0.0,
+            end_angle: std::f32::consts::TAU,
+            segments: value.segments,
+        }
+        .into()
+    }
+}
+
+pub struct Arc {
+    pub center: Vec3,
+    pub radius: f32,
+    pub start_angle: f32,
+    pub end_angle: f32,
+    pub segments: usize,
+}
+
+impl From<Arc> for Polyline {
+    fn from(value: Arc) -> Self {
+        Polyline {
+            vertices: arc_points(
+                value.center,
+                value.radius,
+                value.start_angle,
+                value.end_angle,
+                value.segments,
+            ),
+        }
+    }
+}
+
+/// Rectangle centred on `center`, corners rounded off with quarter circles of `corner_radius`.
+pub struct RoundedRectangle {
+    pub center: Vec3,
+    /// Full width (x) and depth (z).
+    pub size: Vec2,
+    pub corner_radius: f32,
+    pub corner_segments: usize,
+}
+
+impl From<RoundedRectangle> for Polyline {
+    fn from(value: RoundedRectangle) -> Self {
+        use std::f32::consts::{FRAC_PI_2, PI};
+
+        let half = value.size / 2.0;
+        let radius = value.corner_radius.clamp(0.0, half.min_element());
+        let inner = half - radius;
+        let corner = |x: f32, z: f32| value.center + Vec3::new(x, 0.0, z);
+
+        // Corner centres going anticlockwise from the front right, with the angle each corner starts at.
+        let corners = [
+            (corner(inner.x, -inner.y), 0.0),
+            (corner(-inner.x, -inner.y), FRAC_PI_2),
+            (corner(-inner.x, inner.y), PI),
+            (corner(inner.x, inner.y), PI + FRAC_PI_2),
+        ];
+
+        let mut vertices: Vec<Vec3> = corners
+            .iter()
+            .flat_map(|(center, start)| {
+                arc_points(
+                    *center,
+                    radius,
+                    *start,
+                    start + FRAC_PI_2,
+                    value.corner_segments.max(1),
+                )
+            })
+            .collect();
+        // Back to the start to close it
+        vertices.push(vertices[0]);
+
+        Polyline { vertices }
+    }
+}
+
+/// A dashed line along some points. A polyline can't have gaps so each dash is its own polyline.
+pub struct Dashed {
+    pub points: Vec<Vec3>,
+    pub dash: f32,
+    pub gap: f32,
+}
+
+impl From<Dashed> for Vec<Polyline> {
+    fn from(value: Dashed) -> Self {
+        let mut dashes = vec![];
+        if value.dash <= 0.0 {
+            return dashes;
+        }
+
+        let mut current: Vec<Vec3> = vec![];
+        // Whether we're in a dash or a gap, and how much of it is left
+        let mut drawing = true;
+        let mut remaining = value.dash;
+
+        for pair in value.points.windows(2) {
+            let (mut from, to) = (pair[0], pair[1]);
+            if drawing && current.is_empty() {
+                current.push(from);
+            }
+
+            let mut length = from.distance(to);
+            while length > remaining {
+                let split = from.lerp(to, remaining / length);
+                if drawing {
+                    current.push(split);
+                    dashes.push(Polyline {
+                        vertices: std::mem::take(&mut current),
+                    });
+                    remaining = value.gap;
+                } else {
+                    current.push(split);
+                    remaining = value.dash;
+                }
+                drawing = !drawing;
+                length -= from.distance(split);
+                from = split;
+            }
+
+            remaining -= length;
+            if drawing {
+                current.push(to);
+            }
+        }
+
+        if current.len() > 1 {
+            dashes.push(Polyline { vertices: current });
+        }
+        dashes
+    }
+}
+
+/// Cubic bezier from `points[0]` to `points[3]`, pulled towards the two control points in between.
+pub struct Bezier {
+    pub points: [Vec3; 4],
+    pub segments: usize,
+}
+
+impl From<Bezier> for Polyline {
+    fn from(value: Bezier) -> Self {
+        let [p0, p1, p2, p3] = value.points;
+        let segments = value.segments.max(1);
+        let vertices = (0..=segments)
+            .map(|i| {
+                let t = i as f32 / segments as f32;
+                let u = 1.0 - t;
+                p0 * u * u * u + p1 * 3.0 * u * u * t + p2 * 3.0 * u * t * t + p3 * t * t * t
+            })
+            .collect();
+
+        Polyline { vertices }
+    }
+}
+
+/// Smooth curve that passes through every point.
+pub struct CatmullRom {
+    pub points: Vec<Vec3>,
+    pub segments_per_span: usize,
+}
+
+impl From<CatmullRom> for Polyline {
+    fn from(value: CatmullRom) -> Self {
+        let points = value.points;
+        if points.len() < 3 {
+            return Polyline { vertices: points };
+        }
+
+        let segments = value.segments_per_span.max(1);
+        let point = |i: isize| points[i.clamp(0, points.len() as isize - 1) as usize];
+
+        let mut vertices = vec![points[0]];
+        for span in 0..points.len() as isize - 1 {
+            // The ends reuse the first/last point as the missing neighbour
+            let (p0, p1, p2, p3) = (
+                point(span - 1),
+                point(span),
+                point(span + 1),
+                point(span + 2),
+            );
+            for i in 1..=segments {
+                let t = i as f32 / segments as f32;
+                let (t2, t3) = (t * t, t * t * t);
+                vertices.push(
+                    0.5 * (p1 * 2.0
+                        + (p2 - p0) * t
+                        + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * t2
+                        + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * t3),
+                );
+            }
+        }
+
+        Polyline { vertices }
+    }
+}
+
+/// A view cone, out from the origin to the edge of the arc and back.
+pub struct Cone {
+    pub origin: Vec3,
+    /// Which way the middle of the cone points, same as a transform's yaw (0 is looking down -Z).
+    pub yaw: f32,
+    /// Full width of the cone, in radians.
+    pub fov: f32,
+    pub distance: f32,
+    pub segments: usize,
+}
+
+impl From<Cone> for Polyline {
+    fn from(value: Cone) -> Self {
+        // Yaw 0 looks down -Z, which is a quarter turn around from +X
+        let middle = value.yaw + std::f32::consts::FRAC_PI_2;
+        let mut vertices = vec![value.origin];
+        vertices.extend(arc_points(
+            value.origin,
+            value.distance,
+            middle - value.fov / 2.0,
+            middle + value.fov / 2.0,
+            value.segments,
+        ));
+        vertices.push(value.origin);
+
+        Polyline { vertices }
+    }
+}
+
+/// Lay a polyline over the ground, long segments are split up so the line follows the bumps in
+/// between its points. `height` gives the ground height at an x/z position.
+pub struct Draped<F: Fn(f32, f32) -> f32> {
+    pub polyline: Polyline,
+    pub height: F,
+    /// Longest a piece of the line can be before it's split.
+    pub max_segment: f32,
+    /// How far above the ground to draw, to stop the line flickering into it.
+    pub offset: f32,
+}
+
+impl<F: Fn(f32, f32) -> f32> From<Draped<F>> for Polyline {
+    fn from(value: Draped<F>) -> Self {
+        let drape = |point: Vec3| {
+            Vec3::new(
+                point.x,
+                (value.height)(point.x, point.z) + value.offset,
+                point.z,
+            )
+        };
+
+        let source = value.polyline.vertices;
+        let mut vertices: Vec<Vec3> = source.first().copied().map(drape).into_iter().collect();
+        for pair in source.windows(2) {
+            let pieces = if value.max_segment > 0.0 {
+                (pair[0].distance(pair[1]) / value.max_segment)
+                    .ceil()
+                    .max(1.0) as usize
+            } else {
+                1
+            };
+            for i in 1..=pieces {
+                vertices.push(drape(pair[0].lerp(pair[1], i as f32 / pieces as f32)));
+            }
+        }
+
+        Polyline { vertices }
+    }
+}
+
+/// Points along an arc, `segments + 1` of them so both ends are included.
+fn arc_points(center: Vec3, radius: f32, start: f32, end: f32, segments: usize) -> Vec<Vec3> {
+    let segments = segments.max(1);
+    (0..=segments)
+        .map(|i| {
+            let angle = start + (end - start) * i as f32 / segments as f32;
+            center + Vec3::new(angle.cos(), 0.0, -angle.sin()) * radius
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+    use super::*;
+
+    fn assert_near(actual: Vec3, expected: Vec3) {
+        assert!(
+            actual.abs_diff_eq(expected, 0.0001),
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    fn length(vertices: &[Vec3]) -> f32 {
+        vertices
+            .windows(2)
+            .map(|pair| pair[0].distance(pair[1]))
+            .sum()
+    }
+
+    #[test]
+    fn square_goes_round_from_the_origin_and_closes() {
+        let polyline: Polyline = Square(Vec3::new(2.0, 0.0, 3.0)).into();
+
+        assert_eq!(
+            polyline.vertices,
+            vec![
+                Vec3::ZERO,
+                Vec3::new(0.0, 0.0, 3.0),
+                Vec3::new(2.0, 0.0, 3.0),
+                Vec3::new(2.0, 0.0, 0.0),
+                Vec3::ZERO,
+            ]
+        );
+    }
+
+    #[test]
+    fn path_without_a_head_is_just_its_points() {
+        let points = vec![Vec3::ZERO, Vec3::X, Vec3::new(1.0, 0.0, 1.0)];
+        let polyline: Polyline = Path {
+            points: points.clone(),
+            arrow_head: None,
+        }
+        .into();
+
+        assert_eq!(polyline.vertices, points);
+    }
+
+    #[test]
+    fn arrow_head_barbs_sit_either_side_of_the_shaft() {
+        let polyline: Polyline = Arrow {
+            from: Vec3::ZERO,
+            to: Vec3::new(0.0, 0.0, -4.0),
+            head_size: 1.0,
+        }
+        .into();
+
+        let vertices = polyline.vertices;
+        assert_eq!(vertices.len(), 5);
+        assert_near(vertices[1], Vec3::new(0.0, 0.0, -4.0));
+        // Out to a barb and back to the tip before the other one
+        assert_near(vertices[3], vertices[1]);
+        assert_near(vertices[2], Vec3::new(-0.5, 0.0, -3.0));
+        assert_near(vertices[4], Vec3::new(0.5, 0.0, -3.0));
+    }
+
+    #[test]
+    fn circle_is_closed_with_every_point_on_the_radius() {
+        let center = Vec3::new(1.0, 2.0, 3.0);
+        let polyline: Polyline = Circle {
+            center,
+            radius: 2.0,
+            segments: 16,
+        }
+        .into();
+
+        assert_eq!(polyline.vertices.len(), 17);
+        assert_near(polyline.vertices[0], *polyline.vertices.last().unwrap());
+        for vertex in &polyline.vertices {
+            assert!((vertex.distance(center) - 2.0).abs() < 0.0001);
+            assert_eq!(vertex.y, center.y);
+        }
+    }
+
+    #[test]
+    fn arc_starts_at_x_and_turns_towards_negative_z() {
+        let polyline: Polyline = Arc {
+            center: Vec3::ZERO,
+            radius: 1.0,
+            start_angle: 0.0,
+            end_angle: FRAC_PI_2,
+            segments: 4,
+        }
+        .into();
+
+        assert_eq!(polyline.vertices.len(), 5);
+        assert_near(polyline.vertices[0], Vec3::X);
+        assert_near(polyline.vertices[4], Vec3::NEG_Z);
+    }
+
+    #[test]
+    fn arc_with_no_segments_still_has_both_ends() {
+        let polyline: Polyline = Arc {
+            center: Vec3::ZERO,
+            radius: 1.0,
+            start_angle: 0.0,
+            end_angle: PI,
+            segments: 0,
+        }
+        .into();
+
+        assert_eq!(polyline.vertices.len(), 2);
+        assert_near(polyline.vertices[1], Vec3::NEG_X);
+    }
+
+    #[test]
+    fn rounded_rectangle_is_closed_and_fits_its_size() {
+        let polyline: Polyline = RoundedRectangle {
+            center: Vec3::new(1.0, 0.0, 1.0),
+            size: Vec2::new(4.0, 2.0),
+            corner_radius: 0.5,
+            corner_segments: 4,
+        }
+        .into();
+
+        let vertices = &polyline.vertices;
+        assert_eq!(vertices.len(), 4 * 5 + 1);
+        assert_near(vertices[0], *vertices.last().unwrap());
+        let min = vertices
+            .iter()
+            .fold(Vec3::splat(f32::MAX), |a, b| a.min(*b));
+        let max = vertices
+            .iter()
+            .fold(Vec3::splat(f32::MIN), |a, b| a.max(*b));
+        assert_near(min, Vec3::new(-1.0, 0.0, 0.0));
+        assert_near(max, Vec3::new(3.0, 0.0, 2.0));
+    }
+
+    #[test]
+    fn rounded_rectangle_radius_is_clamped_to_half_the_short_side() {
+        let polyline: Polyline = RoundedRectangle {
+            center: Vec3::ZERO,
+            size: Vec2::new(4.0, 2.0),
+            corner_radius: 5.0,
+            corner_segments: 2,
+        }
+        .into();
+
+        for vertex in &polyline.vertices {
+            assert!(vertex.x.abs() <= 2.0001 && vertex.z.abs() <= 1.0001);
+        }
+        // Clamped to 1 rather than dropped, so the short ends are half circles
+        assert_near(polyline.vertices[0], Vec3::new(2.0, 0.0, 0.0));
+        assert_near(polyline.vertices[2], Vec3::new(1.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn rounded_rectangle_with_a_negative_radius_has_square_corners() {
+        let polyline: Polyline = RoundedRectangle {
+            center: Vec3::ZERO,
+            size: Vec2::new(4.0, 2.0),
+            corner_radius: -1.0,
+            corner_segments: 2,
+        }
+        .into();
+
+        assert_near(polyline.vertices[0], Vec3::new(2.0, 0.0, -1.0));
+        for vertex in &polyline.vertices {
+            assert!((vertex.x.abs() - 2.0).abs() < 0.0001 && (vertex.z.abs() - 1.0).abs() < 0.0001);
+        }
+    }
+
+    #[test]
+    fn rounded_rectangle_with_no_corner_segments_still_closes() {
+        let polyline: Polyline = RoundedRectangle {
+            center: Vec3::ZERO,
+            size: Vec2::new(4.0, 2.0),
+            corner_radius: 0.5,
+            corner_segments: 0,
+        }
+        .into();
+
+        // Treated as one segment, both ends of each corner and the start again
+        assert_eq!(polyline.vertices.len(), 9);
+        assert_eq!(polyline.vertices[0], polyline.vertices[8]);
+    }
+
+    #[test]
+    fn dashes_are_dash_long_with_gaps_between() {
+        let dashes: Vec<Polyline> = Dashed {
+            points: vec![Vec3::ZERO, Vec3::new(10.0, 0.0, 0.0)],
+            dash: 1.0,
+            gap: 1.0,
+        }
+        .into();
+
+        assert_eq!(dashes.len(), 5);
+        for (i, dash) in dashes.iter().enumerate() {
+            assert!((length(&dash.vertices) - 1.0).abs() < 0.0001);
+            assert_near(dash.vertices[0], Vec3::new(i as f32 * 2.0, 0.0, 0.0));
+        }
+    }
+
+    #[test]
+    fn dashes_carry_on_round_corners() {
+        let dashes: Vec<Polyline> = Dashed {
+            points: vec![
+                Vec3::ZERO,
+                Vec3::new(1.5, 0.0, 0.0),
+                Vec3::new(1.5, 0.0, 3.0),
+            ],
+            dash: 2.0,
+            gap: 0.5,
+        }
+        .into();
+
+        // The first dash bends round the corner so it has the corner as a vertex
+        assert_eq!(dashes[0].vertices.len(), 3);
+        assert_near(dashes[0].vertices[1], Vec3::new(1.5, 0.0, 0.0));
+        assert!((length(&dashes[0].vertices) - 2.0).abs() < 0.0001);
+        assert_near(dashes[1].vertices[0], Vec3::new(1.5, 0.0, 1.0));
+    }
+
+    #[test]
+    fn zero_length_dashes_draw_nothing() {
+        let dashes: Vec<Polyline> = Dashed {
+            points: vec![Vec3::ZERO, Vec3::X],
+            dash: 0.0,
+            gap: 1.0,
         }
+        .into();
+
+        assert!(dashes.is_empty());
+    }
+
+    #[test]
+    fn bezier_runs_from_the_first_point_to_the_last() {
+        let points = [
+            Vec3::ZERO,
+            Vec3::new(0.0, 0.0, -2.0),
+            Vec3::new(2.0, 0.0, -2.0),
+            Vec3::new(2.0, 0.0, 0.0),
+        ];
+        let polyline: Polyline = Bezier {
+            points,
+            segments: 8,
+        }
+        .into();
+
+        assert_eq!(polyline.vertices.len(), 9);
+        assert_near(polyline.vertices[0], points[0]);
+        assert_near(polyline.vertices[8], points[3]);
+        // Symmetric control points put the middle halfway across, three quarters of the way up
+        assert_near(polyline.vertices[4], Vec3::new(1.0, 0.0, -1.5));
+    }
+
+    #[test]
+    fn bezier_with_no_segments_is_a_straight_line() {
+        let points = [Vec3::ZERO, Vec3::NEG_Z, Vec3::X, Vec3::new(2.0, 0.0, 0.0)];
+        let polyline: Polyline = Bezier {
+            points,
+            segments: 0,
+        }
+        .into();
+
+        assert_eq!(polyline.vertices, vec![points[0], points[3]]);
+    }
+
+    #[test]
+    fn catmull_rom_passes_through_every_point() {
+        let points = vec![
+            Vec3::ZERO,
+            Vec3::new(1.0, 0.0, -1.0),
+            Vec3::new(2.0, 0.0, 1.0),
+            Vec3::new(4.0, 0.0, 0.0),
+        ];
+        let polyline: Polyline = CatmullRom {
+            points: points.clone(),
+            segments_per_span: 5,
+        }
+        .into();
+
+        assert_eq!(polyline.vertices.len(), 1 + 3 * 5);
+        for (i, point) in points.iter().enumerate() {
+            assert_near(polyline.vertices[i * 5], *point);
+        }
+    }
+
+    #[test]
+    fn catmull_rom_with_too_few_points_is_a_straight_line() {
+        let points = vec![Vec3::ZERO, Vec3::X];
+        let polyline: Polyline = CatmullRom {
+            points: points.clone(),
+            segments_per_span: 5,
+        }
+        .into();
+
+        assert_eq!(polyline.vertices, points);
+    }
+
+    #[test]
+    fn catmull_rom_with_no_segments_joins_the_points() {
+        let points = vec![Vec3::ZERO, Vec3::X, Vec3::new(1.0, 0.0, 1.0)];
+        let polyline: Polyline = CatmullRom {
+            points: points.clone(),
+            segments_per_span: 0,
+        }
+        .into();
+
+        assert_eq!(polyline.vertices.len(), 3);
+        for (vertex, point) in polyline.vertices.iter().zip(&points) {
+            assert_near(*vertex, *point);
+        }
+    }
+
+    #[test]
+    fn cone_goes_out_and_back_to_its_origin() {
+        let origin = Vec3::new(1.0, 0.5, 1.0);
+        let polyline: Polyline = Cone {
+            origin,
+            yaw: 0.0,
+            fov: FRAC_PI_2,
+            distance: 3.0,
+            segments: 4,
+        }
+        .into();
+
+        let vertices = &polyline.vertices;
+        assert_eq!(vertices.len(), 4 + 1 + 2);
+        assert_near(vertices[0], origin);
+        assert_near(*vertices.last().unwrap(), origin);
+        // Yaw 0 looks down -Z, same as an unrotated transform
+        assert_near(vertices[3], origin + Vec3::NEG_Z * 3.0);
+        for vertex in &vertices[1..vertices.len() - 1] {
+            assert!((vertex.distance(origin) - 3.0).abs() < 0.0001);
+        }
+    }
+
+    #[test]
+    fn cone_turns_with_its_yaw() {
+        let polyline: Polyline = Cone {
+            origin: Vec3::ZERO,
+            yaw: -FRAC_PI_2,
+            fov: 0.5,
+            distance: 2.0,
+            segments: 2,
+        }
+        .into();
+
+        // Same quarter turn as `Transform::rotate_y(-FRAC_PI_2)`, looking down +X
+        let forward = Quat::from_rotation_y(-FRAC_PI_2) * Vec3::NEG_Z * 2.0;
+        assert_near(polyline.vertices[2], forward);
+    }
+
+    #[test]
+    fn draped_lines_follow_the_ground() {
+        let height = |x: f32, z: f32| (x + z) * 0.5;
+        let polyline: Polyline = Draped {
+            polyline: Polyline {
+                vertices: vec![Vec3::ZERO, Vec3::new(2.0, 0.0, 0.0)],
+            },
+            height,
+            max_segment: 0.5,
+            offset: 0.1,
+        }
+        .into();
+
+        assert_eq!(polyline.vertices.len(), 5);
+        for vertex in &polyline.vertices {
+            assert!((vertex.y - (height(vertex.x, vertex.z) + 0.1)).abs() < 0.0001);
+        }
+        assert_near(polyline.vertices[4], Vec3::new(2.0, 1.1, 0.0));
+    }
+
+    #[test]
+    fn draping_without_a_max_segment_keeps_the_points() {
+        let polyline: Polyline = Draped {
+            polyline: Polyline {
+                vertices: vec![Vec3::ZERO, Vec3::X, Vec3::new(1.0, 0.0, TAU)],
+            },
+            height: |_, _| 1.0,
+            max_segment: 0.0,
+            offset: 0.0,
+        }
+        .into();
+
+        assert_eq!(polyline.vertices.len(), 3);
+        assert!(polyline.vertices.iter().all(|vertex| vertex.y == 1.0));
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,5 @@
+use bevy::prelude::*;
+
+/// The UI image the minimap is drawn into, clicks on it move the camera or give orders.
+#[derive(Component)]
+pub struct MinimapPanel;
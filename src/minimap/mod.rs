@@ -0,0 +1,21 @@
+use bevy::prelude::*;
+
+pub mod components;
+pub mod resources;
+mod systems;
+
+use crate::intent::InputStage;
+use resources::*;
+use systems::*;
+
+pub struct MinimapPlugin;
+
+impl Plugin for MinimapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MinimapSettings>()
+            .init_resource::<Minimap>()
+            .add_startup_system(spawn_minimap)
+            .add_system(draw_minimap)
+            .add_system(minimap_click.in_set(InputStage::Ui));
+    }
+}
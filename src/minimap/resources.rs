@@ -0,0 +1,36 @@
+use bevy::prelude::*;
+
+#[derive(Resource)]
+pub struct MinimapSettings {
+    /// Size of the panel on screen, in pixels.
+    pub panel_size: f32,
+    /// Size of the image the map is drawn into, in pixels.
+    pub resolution: u32,
+    /// How often to redraw, in seconds.
+    pub refresh: f32,
+    /// Only show enemies the player can currently see (`VisibleToPlayer`).
+    pub fog_of_war: bool,
+    pub player_color: Color,
+    pub enemy_color: Color,
+    pub frustum_color: Color,
+}
+
+impl Default for MinimapSettings {
+    fn default() -> Self {
+        MinimapSettings {
+            panel_size: 200.0,
+            resolution: 128,
+            refresh: 0.1,
+            fog_of_war: true,
+            player_color: Color::RED,
+            enemy_color: Color::WHITE,
+            frustum_color: Color::YELLOW,
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Minimap {
+    pub image: Handle<Image>,
+    pub timer: Timer,
+}
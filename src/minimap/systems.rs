@@ -0,0 +1,240 @@
+use bevy::{
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    ui::RelativeCursorPosition,
+};
+
+use crate::{
+    camera::{components::PlayerCamera, resources::CameraJump},
+    intent::resources::PointerIntents,
+    orders::resources::{IssueOrder, OrderKind, OrderTarget},
+    units::components::{Enemy, Player},
+    vision::components::VisibleToPlayer,
+    world_gen::resources::Ground,
+};
+
+use super::{components::*, resources::*};
+
+pub fn spawn_minimap(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut minimap: ResMut<Minimap>,
+    settings: Res<MinimapSettings>,
+) {
+    let image = Image::new_fill(
+        Extent3d {
+            width: settings.resolution,
+            height: settings.resolution,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    minimap.image = images.add(image);
+    minimap.timer = Timer::from_seconds(settings.refresh, TimerMode::Repeating);
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    right: Val::Px(10.0),
+                    bottom: Val::Px(10.0),
+                    ..default()
+                },
+                padding: UiRect::all(Val::Px(2.0)),
+                ..default()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.8).into(),
+            ..default()
+        })
+        .insert(Name::new("Minimap"))
+        .with_children(|parent| {
+            parent
+                .spawn(ImageBundle {
+                    style: Style {
+                        size: Size::all(Val::Px(settings.panel_size)),
+                        ..default()
+                    },
+                    image: UiImage::new(minimap.image.clone()),
+                    focus_policy: bevy::ui::FocusPolicy::Block,
+                    ..default()
+                })
+                .insert(MinimapPanel)
+                .insert(Interaction::default())
+                .insert(RelativeCursorPosition::default());
+        });
+}
+
+/// Redraw the minimap image, terrain, units and what the camera can currently see.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_minimap(
+    time: Res<Time>,
+    mut minimap: ResMut<Minimap>,
+    settings: Res<MinimapSettings>,
+    ground: Res<Ground>,
+    materials: Res<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+    players: Query<&GlobalTransform, With<Player>>,
+    enemies: Query<(&GlobalTransform, Option<&VisibleToPlayer>), With<Enemy>>,
+    camera: Query<(&Camera, &GlobalTransform), With<PlayerCamera>>,
+) {
+    // No need to redraw every frame, units don't move that fast
+    if !minimap.timer.tick(time.raw_delta()).just_finished() {
+        return;
+    }
+    let Some(image) = images.get_mut(&minimap.image) else { return; };
+
+    let resolution = settings.resolution as i32;
+    let size = ground.size as f32;
+    // World x goes right, world z goes down the map
+    let to_pixel = |world: Vec3| {
+        let normalized = Vec2::new(world.x, world.z) / size + 0.5;
+        (normalized * resolution as f32).floor().as_ivec2()
+    };
+
+    // Darken the ground a little so units stand out on it
+    let terrain = materials
+        .get(&ground.material)
+        .map(|material| material.base_color)
+        .unwrap_or(Color::DARK_GREEN)
+        * 0.6;
+    let terrain = to_rgba8(terrain);
+    for pixel in image.data.chunks_exact_mut(4) {
+        pixel.copy_from_slice(&terrain);
+    }
+
+    let enemy_color = to_rgba8(settings.enemy_color);
+    for (transform, visible) in enemies.iter() {
+        if settings.fog_of_war && visible.is_none() {
+            continue;
+        }
+        plot_blip(
+            &mut image.data,
+            resolution,
+            to_pixel(transform.translation()),
+            enemy_color,
+        );
+    }
+
+    // Players last so they're always on top
+    let player_color = to_rgba8(settings.player_color);
+    for transform in players.iter() {
+        plot_blip(
+            &mut image.data,
+            resolution,
+            to_pixel(transform.translation()),
+            player_color,
+        );
+    }
+
+    // Outline of what's on screen, the corners of the view projected down onto the ground.
+    let Ok((camera, camera_location)) = camera.get_single() else { return; };
+    let Some(viewport) = camera.logical_viewport_size() else { return; };
+    let corners = [
+        Vec2::ZERO,
+        Vec2::new(viewport.x, 0.0),
+        viewport,
+        Vec2::new(0.0, viewport.y),
+    ]
+    .map(|corner| {
+        camera
+            .viewport_to_world(camera_location, corner)
+            .map(|ray| to_pixel(ray_to_ground(ray, size)))
+    });
+
+    let frustum_color = to_rgba8(settings.frustum_color);
+    for i in 0..corners.len() {
+        if let (Some(a), Some(b)) = (corners[i], corners[(i + 1) % corners.len()]) {
+            draw_line(&mut image.data, resolution, a, b, frustum_color);
+        }
+    }
+}
+
+/// Left click moves the camera there, right click sends the selected units there.
+pub fn minimap_click(
+    mouse_btn: Res<Input<MouseButton>>,
+    mut intents: ResMut<PointerIntents>,
+    ground: Res<Ground>,
+    panel: Query<&RelativeCursorPosition, With<MinimapPanel>>,
+    mut jumps: EventWriter<CameraJump>,
+    mut orders: EventWriter<IssueOrder>,
+) {
+    let Ok(cursor) = panel.get_single() else { return; };
+    let (true, Some(normalized)) = (cursor.mouse_over(), cursor.normalized) else { return; };
+
+    let size = ground.size as f32;
+    let target = Vec3::new(
+        (normalized.x - 0.5) * size,
+        0.0,
+        (normalized.y - 0.5) * size,
+    );
+
+    if mouse_btn.just_pressed(MouseButton::Left) {
+        jumps.send(CameraJump::to(target));
+    }
+
+    if intents.consume_click(MouseButton::Right) {
+        orders.send(IssueOrder::new(
+            OrderKind::Move,
+            Some(OrderTarget::Position(target)),
+        ));
+    }
+}
+
+/// Where a view ray hits the ground plane, rays that never come down are cut off at `far`.
+fn ray_to_ground(ray: Ray, far: f32) -> Vec3 {
+    if ray.direction.y < -f32::EPSILON {
+        let distance = (-ray.origin.y / ray.direction.y).min(far);
+        ray.origin + ray.direction * distance
+    } else {
+        let flat = Vec3::new(ray.direction.x, 0.0, ray.direction.z).normalize_or_zero();
+        Vec3::new(ray.origin.x, 0.0, ray.origin.z) + flat * far
+    }
+}
+
+fn to_rgba8(color: Color) -> [u8; 4] {
+    color
+        .as_rgba_f32()
+        .map(|c| (c.clamp(0.0, 1.0) * 255.0) as u8)
+}
+
+fn plot(data: &mut [u8], resolution: i32, pixel: IVec2, color: [u8; 4]) {
+    if pixel.x < 0 || pixel.y < 0 || pixel.x >= resolution || pixel.y >= resolution {
+        return;
+    }
+    let index = ((pixel.y * resolution + pixel.x) * 4) as usize;
+    data[index..index + 4].copy_from_slice(&color);
+}
+
+/// Units are 2x2 so they're actually visible on the map.
+fn plot_blip(data: &mut [u8], resolution: i32, pixel: IVec2, color: [u8; 4]) {
+    for offset in [IVec2::ZERO, IVec2::X, IVec2::Y, IVec2::ONE] {
+        plot(data, resolution, pixel + offset, color);
+    }
+}
+
+/// Bresenham, anything off the edge of the map is just skipped.
+fn draw_line(data: &mut [u8], resolution: i32, from: IVec2, to: IVec2, color: [u8; 4]) {
+    let delta = IVec2::new((to.x - from.x).abs(), -(to.y - from.y).abs());
+    let step = IVec2::new((to.x - from.x).signum(), (to.y - from.y).signum());
+    let mut error = delta.x + delta.y;
+    let mut pixel = from;
+
+    loop {
+        plot(data, resolution, pixel, color);
+        if pixel == to {
+            break;
+        }
+        let doubled = 2 * error;
+        if doubled >= delta.y {
+            error += delta.y;
+            pixel.x += step.x;
+        }
+        if doubled <= delta.x {
+            error += delta.x;
+            pixel.y += step.y;
+        }
+    }
+}
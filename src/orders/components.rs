@@ -0,0 +1,16 @@
+use bevy::prelude::*;
+
+/// Don't move, fight whatever comes in range but never chase it.
+#[derive(Component)]
+pub struct HoldPosition;
+
+/// Walk back and forth between two points until told otherwise.
+#[derive(Component)]
+pub struct Patrol {
+    pub from: Vec3,
+    pub to: Vec3,
+}
+
+/// Shrinking ring on the ground where an order was given, gone once the timer runs out.
+#[derive(Component)]
+pub struct OrderMarker(pub Timer);
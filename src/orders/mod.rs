@@ -0,0 +1,52 @@
+use bevy::prelude::*;
+
+pub mod components;
+pub mod resources;
+mod systems;
+
+use crate::{game_state::GameState, intent::InputStage, simulation::SimulationSet};
+use resources::*;
+use systems::*;
+
+/// Orders the player can give their selected units, the registry is what the command card shows.
+pub struct OrdersPlugin;
+
+impl Plugin for OrdersPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(OrderSimulationPlugin)
+            .init_resource::<OrderRegistry>()
+            .init_resource::<PendingOrder>()
+            .init_resource::<OrderFeedbackAssets>()
+            .init_resource::<PathLines>()
+            .init_resource::<MouseLocation>()
+            .add_event::<IssueOrder>()
+            .add_system(order_hotkeys.before(InputStage::Targeting))
+            .add_system(target_pending_order.in_set(InputStage::Targeting))
+            .add_system(track_mouse_location)
+            .add_system(mouse_click_set_movement_target.in_set(InputStage::World))
+            .add_system(queue_orders.after(InputStage::World))
+            .add_system(spawn_order_markers.after(InputStage::World))
+            .add_system(animate_order_markers)
+            .add_system(draw_unit_paths)
+            .add_system(clear_order_feedback.in_schedule(OnEnter(GameState::MainMenu)));
+    }
+}
+
+/// Just the part of orders that runs in the simulation, carrying out order commands and patrols.
+/// Enough on its own for the headless runner.
+pub struct OrderSimulationPlugin;
+
+impl Plugin for OrderSimulationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            execute_orders
+                .in_set(SimulationSet::Commands)
+                .in_schedule(CoreSchedule::FixedUpdate),
+        )
+        .add_system(
+            patrol
+                .in_set(SimulationSet::Movement)
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
+    }
+}
@@ -0,0 +1,194 @@
+use bevy::{prelude::*, utils::HashMap};
+use bevy_polyline::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum OrderKind {
+    Move,
+    Stop,
+    Hold,
+    Patrol,
+    Attack,
+}
+
+/// What an order needs picked in the world before it can be issued.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TargetKind {
+    None,
+    Position,
+    Unit,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OrderTarget {
+    Position(Vec3),
+    Unit(Entity),
+}
+
+/// Give every selected unit an order.
+pub struct IssueOrder {
+    pub kind: OrderKind,
+    pub target: Option<OrderTarget>,
+}
+
+impl IssueOrder {
+    pub fn new(kind: OrderKind, target: Option<OrderTarget>) -> Self {
+        IssueOrder { kind, target }
+    }
+}
+
+/// How an order is shown and triggered, the command card is built from these.
+#[derive(Clone, Debug)]
+pub struct OrderDefinition {
+    pub kind: OrderKind,
+    pub label: &'static str,
+    pub hotkey: KeyCode,
+    pub tooltip: &'static str,
+    pub target: TargetKind,
+}
+
+impl OrderDefinition {
+    /// Orders without a target go straight out, the rest wait for a click in the world.
+    pub fn activate(&self, pending: &mut PendingOrder, orders: &mut EventWriter<IssueOrder>) {
+        match self.target {
+            TargetKind::None => {
+                pending.0 = None;
+                orders.send(IssueOrder::new(self.kind, None));
+            }
+            _ => pending.0 = Some(self.kind),
+        }
+    }
+}
+
+/// Every order the player can give, in the order they show up on the command card.
+#[derive(Resource)]
+pub struct OrderRegistry {
+    pub orders: Vec<OrderDefinition>,
+}
+
+impl OrderRegistry {
+    /// Add an order, or replace the existing definition for the same kind.
+    pub fn register(&mut self, definition: OrderDefinition) {
+        match self.orders.iter_mut().find(|o| o.kind == definition.kind) {
+            Some(existing) => *existing = definition,
+            None => self.orders.push(definition),
+        }
+    }
+
+    pub fn get(&self, kind: OrderKind) -> Option<&OrderDefinition> {
+        self.orders.iter().find(|o| o.kind == kind)
+    }
+}
+
+impl Default for OrderRegistry {
+    fn default() -> Self {
+        // Hotkeys stay clear of WASD, they pan the camera
+        let mut registry = OrderRegistry { orders: vec![] };
+        registry.register(OrderDefinition {
+            kind: OrderKind::Move,
+            label: "Move",
+            hotkey: KeyCode::M,
+            tooltip: "Walk to a spot on the ground.",
+            target: TargetKind::Position,
+        });
+        registry.register(OrderDefinition {
+            kind: OrderKind::Stop,
+            label: "Stop",
+            hotkey: KeyCode::X,
+            tooltip: "Drop whatever order the units have.",
+            target: TargetKind::None,
+        });
+        registry.register(OrderDefinition {
+            kind: OrderKind::Hold,
+            label: "Hold",
+            hotkey: KeyCode::H,
+            tooltip: "Stay put, fight anything in range but don't chase it.",
+            target: TargetKind::None,
+        });
+        registry.register(OrderDefinition {
+            kind: OrderKind::Patrol,
+            label: "Patrol",
+            hotkey: KeyCode::P,
+            tooltip: "Walk back and forth between here and a spot on the ground.",
+            target: TargetKind::Position,
+        });
+        registry.register(OrderDefinition {
+            kind: OrderKind::Attack,
+            label: "Attack",
+            hotkey: KeyCode::T,
+            tooltip: "Chase down and attack a unit.",
+            target: TargetKind::Unit,
+        });
+        registry
+    }
+}
+
+/// An order waiting for the player to click its target.
+#[derive(Resource, Default)]
+pub struct PendingOrder(pub Option<OrderKind>);
+
+/// Shared assets for order feedback, markers on the ground and selected units' paths.
+#[derive(Resource)]
+pub struct OrderFeedbackAssets {
+    pub marker: Handle<Mesh>,
+    pub move_marker: Handle<StandardMaterial>,
+    pub attack_marker: Handle<StandardMaterial>,
+    pub move_path: Handle<PolylineMaterial>,
+    pub attack_path: Handle<PolylineMaterial>,
+}
+
+impl FromWorld for OrderFeedbackAssets {
+    fn from_world(world: &mut World) -> Self {
+        let marker = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(Mesh::from(shape::Torus {
+                radius: 0.5,
+                ring_radius: 0.05,
+                subdivisions_segments: 32,
+                subdivisions_sides: 6,
+            }));
+
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        let mut marker_material = |color: Color| {
+            materials.add(StandardMaterial {
+                base_color: color,
+                unlit: true,
+                ..default()
+            })
+        };
+        let move_marker = marker_material(Color::GREEN);
+        let attack_marker = marker_material(Color::RED);
+
+        let mut polyline_materials = world.resource_mut::<Assets<PolylineMaterial>>();
+        let mut path_material = |color: Color| {
+            polyline_materials.add(PolylineMaterial {
+                color,
+                width: 3.0,
+                ..default()
+            })
+        };
+
+        OrderFeedbackAssets {
+            marker,
+            move_marker,
+            attack_marker,
+            move_path: path_material(Color::rgba(0.2, 1.0, 0.3, 0.8)),
+            attack_path: path_material(Color::rgba(1.0, 0.2, 0.2, 0.8)),
+        }
+    }
+}
+
+/// A unit's path line, and what it's drawn with so it's only changed when it has to be.
+pub struct PathLine {
+    pub line: Entity,
+    pub polyline: Handle<Polyline>,
+    pub material: Handle<PolylineMaterial>,
+}
+
+/// The path line drawn for each selected unit that's going somewhere.
+#[derive(Resource, Default)]
+pub struct PathLines(pub HashMap<Entity, PathLine>);
+
+/// Where on the ground (or whatever else) the cursor is pointing.
+#[derive(Resource, Default)]
+pub struct MouseLocation(pub Option<Vec3>);
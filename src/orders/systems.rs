@@ -0,0 +1,397 @@
+use bevy::{
+    input::mouse::MouseMotion,
+    prelude::*,
+    utils::{HashMap, HashSet},
+    window::PrimaryWindow,
+};
+use bevy_polyline::prelude::*;
+use bevy_rapier3d::prelude::RapierContext;
+
+use crate::{
+    camera::{components::PlayerCamera, screen_ray_to_entity},
+    combat::components::{AttackTarget, Health},
+    intent::resources::PointerIntents,
+    line_drawing::Path,
+    replay::resources::{CommandTarget, PendingCommands, PlayerCommand, TickCommands},
+    save::components::UnitId,
+    selection::components::SelectedUnit,
+    units::components::WalkToLocation,
+};
+
+use super::{components::*, resources::*};
+
+pub fn order_hotkeys(
+    input_keys: Res<Input<KeyCode>>,
+    registry: Res<OrderRegistry>,
+    mut pending: ResMut<PendingOrder>,
+    mut orders: EventWriter<IssueOrder>,
+    selected: Query<(), With<SelectedUnit>>,
+) {
+    if selected.is_empty() {
+        return;
+    }
+
+    for definition in registry.orders.iter() {
+        if input_keys.just_pressed(definition.hotkey) {
+            definition.activate(&mut pending, &mut orders);
+        }
+    }
+}
+
+/// While an order is waiting for a target, left click picks it and right click/escape cancels.
+/// The left button is held onto the whole time so selection doesn't see any of it.
+#[allow(clippy::too_many_arguments)]
+pub fn target_pending_order(
+    mut pending: ResMut<PendingOrder>,
+    mut intents: ResMut<PointerIntents>,
+    input_keys: Res<Input<KeyCode>>,
+    registry: Res<OrderRegistry>,
+    rapier_context: Res<RapierContext>,
+    mut orders: EventWriter<IssueOrder>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    camera: Query<(&Camera, &GlobalTransform), With<PlayerCamera>>,
+    targetable: Query<(), (With<Health>, Without<SelectedUnit>)>,
+) {
+    let Some(kind) = pending.0 else { return; };
+    if input_keys.just_pressed(KeyCode::Escape) || intents.consume_click(MouseButton::Right) {
+        pending.0 = None;
+        return;
+    }
+
+    // The cursor is over the UI, leave the click to it
+    if intents.is_claimed(MouseButton::Left) {
+        return;
+    }
+    let clicked = intents.clicked(MouseButton::Left);
+    intents.claim(MouseButton::Left);
+    if !clicked {
+        return;
+    }
+
+    let Some(definition) = registry.get(kind) else { return; };
+    let Ok(window) = windows.get_single() else { return; };
+    let Some(cursor_position) = window.cursor_position() else { return; };
+    let Ok((camera, camera_location)) = camera.get_single() else { return; };
+    let Some((entity, location)) = screen_ray_to_entity(camera, &rapier_context, camera_location, cursor_position) else { return; };
+
+    let target = match definition.target {
+        TargetKind::None => None,
+        TargetKind::Position => Some(OrderTarget::Position(location)),
+        TargetKind::Unit if targetable.contains(entity) => Some(OrderTarget::Unit(entity)),
+        // Missed, keep waiting for a unit
+        TargetKind::Unit => return,
+    };
+
+    orders.send(IssueOrder::new(kind, target));
+    pending.0 = None;
+}
+
+/// Turn orders for the current selection into commands for the next simulation tick, where each
+/// one replaces whatever those units were doing before.
+pub fn queue_orders(
+    mut orders: EventReader<IssueOrder>,
+    mut pending: ResMut<PendingCommands>,
+    selected: Query<&UnitId, With<SelectedUnit>>,
+    ids: Query<&UnitId>,
+) {
+    for order in orders.iter() {
+        let units: Vec<UnitId> = selected.iter().copied().collect();
+        if units.is_empty() {
+            continue;
+        }
+
+        let target = match order.target {
+            Some(OrderTarget::Position(location)) => Some(CommandTarget::Position(location)),
+            Some(OrderTarget::Unit(unit)) => match ids.get(unit) {
+                Ok(id) => Some(CommandTarget::Unit(*id)),
+                // Not something a unit can be ordered to attack
+                Err(_) => continue,
+            },
+            None => None,
+        };
+
+        pending.0.push(PlayerCommand::Order {
+            units,
+            kind: order.kind,
+            target,
+        });
+    }
+}
+
+pub fn execute_orders(
+    mut commands: Commands,
+    tick_commands: Res<TickCommands>,
+    units: Query<(Entity, &UnitId, &Transform)>,
+) {
+    let mut by_id = None;
+
+    for command in tick_commands.0.iter() {
+        let PlayerCommand::Order {
+            units: ordered,
+            kind,
+            target,
+        } = command
+        else {
+            continue;
+        };
+        let by_id = by_id.get_or_insert_with(|| {
+            units
+                .iter()
+                .map(|(entity, id, transform)| (*id, (entity, transform.translation)))
+                .collect::<HashMap<_, _>>()
+        });
+
+        for id in ordered.iter() {
+            // Died since the order was given
+            let Some(&(entity, position)) = by_id.get(id) else { continue; };
+            let mut unit = commands.entity(entity);
+            unit.remove::<(WalkToLocation, AttackTarget, Patrol, HoldPosition)>();
+
+            match (kind, target) {
+                (OrderKind::Move, Some(CommandTarget::Position(location))) => {
+                    unit.insert(WalkToLocation(*location));
+                }
+                (OrderKind::Hold, _) => {
+                    unit.insert(HoldPosition);
+                }
+                (OrderKind::Patrol, Some(CommandTarget::Position(location))) => {
+                    unit.insert(Patrol {
+                        from: position,
+                        to: *location,
+                    })
+                    .insert(WalkToLocation(*location));
+                }
+                (OrderKind::Attack, Some(CommandTarget::Unit(target))) if target != id => {
+                    if let Some(&(target, _)) = by_id.get(target) {
+                        unit.insert(AttackTarget(target));
+                    }
+                }
+                // Stop, or an order missing its target, leaves the unit idle
+                _ => {}
+            }
+        }
+    }
+}
+
+/// How close to the end of a patrol counts as having got there, a bit looser than walking's own.
+const PATROL_END_TOLERANCE: f32 = 0.01;
+
+/// Turn around once we reach the end of the patrol, fights along the way pause it.
+#[allow(clippy::type_complexity)]
+pub fn patrol(
+    mut commands: Commands,
+    mut patrols: Query<
+        (Entity, &Transform, &mut Patrol),
+        (Without<WalkToLocation>, Without<AttackTarget>),
+    >,
+) {
+    for (entity, transform, mut patrol) in patrols.iter_mut() {
+        let patrol = &mut *patrol;
+        // A fight drops the walk wherever it was, carry on to the same end rather than turn back
+        if transform.translation.distance(patrol.to) <= PATROL_END_TOLERANCE {
+            std::mem::swap(&mut patrol.from, &mut patrol.to);
+        }
+        commands.entity(entity).insert(WalkToLocation(patrol.to));
+    }
+}
+
+/// How long an order marker stays on the ground.
+const MARKER_SECONDS: f32 = 0.6;
+/// Lift lines and markers off the ground so they don't flicker into it.
+const GROUND_OFFSET: f32 = 0.05;
+
+/// Drop a marker where each order was sent, green for moves and red for attacks.
+pub fn spawn_order_markers(
+    mut commands: Commands,
+    mut orders: EventReader<IssueOrder>,
+    assets: Res<OrderFeedbackAssets>,
+    targets: Query<&GlobalTransform>,
+) {
+    for order in orders.iter() {
+        let (location, material) = match order.target {
+            Some(OrderTarget::Position(location)) => (location, assets.move_marker.clone()),
+            Some(OrderTarget::Unit(unit)) => match targets.get(unit) {
+                Ok(transform) => (transform.translation(), assets.attack_marker.clone()),
+                Err(_) => continue,
+            },
+            None => continue,
+        };
+
+        commands
+            .spawn(PbrBundle {
+                mesh: assets.marker.clone(),
+                material,
+                transform: Transform::from_translation(on_ground(location)),
+                ..default()
+            })
+            .insert(OrderMarker(Timer::from_seconds(
+                MARKER_SECONDS,
+                TimerMode::Once,
+            )))
+            .insert(Name::new("Order Marker"));
+    }
+}
+
+/// Markers shrink in towards the target then disappear.
+pub fn animate_order_markers(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut markers: Query<(Entity, &mut OrderMarker, &mut Transform)>,
+) {
+    for (entity, mut marker, mut transform) in markers.iter_mut() {
+        // Raw time, feedback for the player shouldn't slow down with the game
+        marker.0.tick(time.raw_delta());
+        if marker.0.finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        let t = marker.0.percent();
+        transform.scale = Vec3::splat(1.0 - t * 0.7);
+    }
+}
+
+/// Draw where each selected unit is heading, with the patrol route back for patrolling units.
+#[allow(clippy::type_complexity)]
+pub fn draw_unit_paths(
+    mut commands: Commands,
+    mut lines: ResMut<PathLines>,
+    mut polylines: ResMut<Assets<Polyline>>,
+    assets: Res<OrderFeedbackAssets>,
+    units: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            Option<&WalkToLocation>,
+            Option<&Patrol>,
+            Option<&AttackTarget>,
+        ),
+        With<SelectedUnit>,
+    >,
+    targets: Query<&GlobalTransform>,
+) {
+    let mut drawn = HashSet::new();
+
+    for (entity, transform, walk_to, patrol, attacking) in units.iter() {
+        let here = on_ground(transform.translation());
+        let (points, material) = match (attacking, walk_to, patrol) {
+            (Some(target), ..) => match targets.get(target.0) {
+                Ok(target) => (
+                    vec![here, on_ground(target.translation())],
+                    assets.attack_path.clone(),
+                ),
+                Err(_) => continue,
+            },
+            (None, Some(walk_to), Some(patrol)) => (
+                vec![here, on_ground(walk_to.0), on_ground(patrol.from)],
+                assets.move_path.clone(),
+            ),
+            (None, Some(walk_to), None) => {
+                (vec![here, on_ground(walk_to.0)], assets.move_path.clone())
+            }
+            _ => continue,
+        };
+
+        let polyline: Polyline = Path {
+            points,
+            arrow_head: Some(0.4),
+        }
+        .into();
+        drawn.insert(entity);
+
+        match lines.0.get_mut(&entity) {
+            Some(path) => {
+                if let Some(existing) = polylines.get_mut(&path.polyline) {
+                    *existing = polyline;
+                }
+                // Only when it goes from moving to attacking or back
+                if path.material != material {
+                    commands.entity(path.line).insert(material.clone());
+                    path.material = material;
+                }
+            }
+            None => {
+                let handle = polylines.add(polyline);
+                let line = commands
+                    .spawn(PolylineBundle {
+                        polyline: handle.clone(),
+                        material: material.clone(),
+                        ..default()
+                    })
+                    .insert(Name::new("Unit Path"))
+                    .id();
+                lines.0.insert(
+                    entity,
+                    PathLine {
+                        line,
+                        polyline: handle,
+                        material,
+                    },
+                );
+            }
+        }
+    }
+
+    // Deselected, arrived or dead
+    lines.0.retain(|unit, path| {
+        let keep = drawn.contains(unit);
+        if !keep {
+            commands.entity(path.line).despawn_recursive();
+        }
+        keep
+    });
+}
+
+fn on_ground(location: Vec3) -> Vec3 {
+    Vec3::new(location.x, GROUND_OFFSET, location.z)
+}
+
+pub fn track_mouse_location(
+    mut commands: Commands,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut mouse: EventReader<MouseMotion>,
+    camera: Query<(&Camera, &GlobalTransform), With<PlayerCamera>>,
+    rapier_context: Res<RapierContext>,
+) {
+    if mouse.iter().len() > 0 {
+        let Ok(window) = windows.get_single() else { return; };
+        let Some(cursor_position) = window.cursor_position() else { return; };
+        let Ok((camera, camera_location)) = camera.get_single() else { return; };
+        let Some((_, loc)) = screen_ray_to_entity(camera, &rapier_context, camera_location, cursor_position) else { return; };
+
+        commands.insert_resource(MouseLocation(Some(loc)));
+    }
+}
+
+pub fn mouse_click_set_movement_target(
+    mut intents: ResMut<PointerIntents>,
+    mouse_loc: Res<MouseLocation>,
+    mut orders: EventWriter<IssueOrder>,
+) {
+    // Only a click is a move order, dragging with right mouse is the camera orbiting.
+    if mouse_loc.0.is_some() && intents.consume_click(MouseButton::Right) {
+        let Some(loc) = mouse_loc.0 else { return; };
+
+        orders.send(IssueOrder::new(
+            OrderKind::Move,
+            Some(OrderTarget::Position(loc)),
+        ));
+    }
+}
+
+/// Back at the main menu, drop anything left over from the last game.
+pub fn clear_order_feedback(
+    mut commands: Commands,
+    mut pending: ResMut<PendingOrder>,
+    mut lines: ResMut<PathLines>,
+    markers: Query<Entity, With<OrderMarker>>,
+) {
+    for marker in markers.iter() {
+        commands.entity(marker).despawn_recursive();
+    }
+    for path in lines.0.values() {
+        commands.entity(path.line).despawn_recursive();
+    }
+    lines.0.clear();
+    pending.0 = None;
+}
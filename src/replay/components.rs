@@ -0,0 +1,5 @@
+use bevy::prelude::*;
+
+/// The status line shown while a replay is playing.
+#[derive(Component)]
+pub struct ReplayStatusText;
@@ -0,0 +1,84 @@
+use bevy::prelude::*;
+
+pub mod components;
+pub mod resources;
+mod systems;
+
+use crate::{
+    game_state::GameState,
+    intent::InputStage,
+    save::resources::LoadGame,
+    simulation::SimulationSet,
+    world_gen::{resources::StartScenario, start_scenario},
+};
+use resources::*;
+use systems::*;
+
+/// Records the player's commands against the tick they happened on, and plays them back from the
+/// scenario's seed. F6 saves the recording, F7 plays it (or takes over from a replay).
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(CommandPlugin)
+            .add_event::<SaveReplay>()
+            .add_startup_system(spawn_replay_status)
+            .add_system(
+                record_selection
+                    .after(InputStage::World)
+                    .run_if(not(resource_exists::<Playback>())),
+            )
+            .add_system(
+                replay_hotkeys
+                    .before(play_replay)
+                    .run_if(in_state(GameState::InGame)),
+            )
+            .add_system(save_replay.after(replay_hotkeys))
+            // Paused from the menu stays paused, seeking would run ticks anyway
+            .add_system(
+                playback_controls
+                    .run_if(resource_exists::<Playback>())
+                    .run_if(in_state(GameState::InGame)),
+            )
+            .add_system(
+                seek_replay
+                    .in_base_set(CoreSet::PreUpdate)
+                    .run_if(in_state(GameState::InGame)),
+            )
+            .add_system(stop_replay.in_schedule(OnEnter(GameState::MainMenu)))
+            .add_system(update_replay_status);
+    }
+}
+
+/// Feeds player commands into the simulation tick by tick, recording them or playing back a
+/// replay's. Enough on its own for the headless runner.
+pub struct CommandPlugin;
+
+impl Plugin for CommandPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PendingCommands>()
+            .init_resource::<TickCommands>()
+            .init_resource::<Recording>()
+            .add_event::<PlayReplay>()
+            .add_system(
+                gather_tick_commands
+                    .in_set(SimulationSet::Input)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_system(
+                replay_selection
+                    .in_set(SimulationSet::Commands)
+                    .run_if(resource_exists::<Playback>())
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            // Restarting the scenario and the recording have to happen on the same frame
+            .add_system(play_replay.before(start_scenario))
+            .add_system(
+                reset_recording
+                    .after(play_replay)
+                    .run_if(on_event::<StartScenario>()),
+            )
+            .add_system(forget_recording_on_load.run_if(on_event::<LoadGame>()))
+            .add_system(finish_replay.run_if(resource_exists::<Playback>()));
+    }
+}
@@ -0,0 +1,131 @@
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    orders::resources::OrderKind,
+    save::{components::UnitId, read_ron_with, write_ron},
+};
+
+/// Bump when `ReplayFile` changes shape, replays from other versions won't play back the same.
+pub const REPLAY_VERSION: u32 = 1;
+
+/// F6 saves the recording to this file in the scenario's save folder and F7 plays it back.
+pub const REPLAY_FILE: &str = "replay.ron";
+
+pub const SAVE_REPLAY_KEY: KeyCode = KeyCode::F6;
+pub const PLAY_REPLAY_KEY: KeyCode = KeyCode::F7;
+
+/// Playback controls, only while a replay is playing. Pausing and speed are the same as in game.
+pub const REPLAY_RESTART_KEY: KeyCode = KeyCode::Home;
+pub const REPLAY_BACK_KEY: KeyCode = KeyCode::PageUp;
+pub const REPLAY_FORWARD_KEY: KeyCode = KeyCode::PageDown;
+
+/// How far the back/forward keys jump, 10 seconds.
+pub const REPLAY_SKIP_TICKS: u64 = 200;
+
+/// Most ticks to fast forward in one frame while jumping, so the window stays responsive.
+pub const MAX_SEEK_TICKS_PER_FRAME: u32 = 100;
+
+/// Something the player did that changes the game. Units are referred to by `UnitId` so the
+/// same command means the same thing when it's played back.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum PlayerCommand {
+    /// The selection changed to exactly these units.
+    Select(Vec<UnitId>),
+    Order {
+        units: Vec<UnitId>,
+        kind: OrderKind,
+        target: Option<CommandTarget>,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum CommandTarget {
+    Position(Vec3),
+    Unit(UnitId),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TickedCommand {
+    pub tick: u64,
+    pub command: PlayerCommand,
+}
+
+/// Commands given since the last tick, they're carried out on the next one.
+#[derive(Resource, Default)]
+pub struct PendingCommands(pub Vec<PlayerCommand>);
+
+/// The commands being carried out this tick.
+#[derive(Resource, Default)]
+pub struct TickCommands(pub Vec<PlayerCommand>);
+
+/// Every command given since the scenario started, ready to be saved as a replay.
+#[derive(Resource, Default)]
+pub struct Recording {
+    pub seed: u64,
+    pub commands: Vec<TickedCommand>,
+    /// Loading a save means the game no longer follows from the seed, so it can't be replayed.
+    pub replayable: bool,
+}
+
+/// Present while a replay is playing, player commands are ignored and the replay's are used.
+#[derive(Resource)]
+pub struct Playback {
+    pub replay: ReplayFile,
+    /// Index of the next command to play.
+    pub next: usize,
+    /// Fast forward (or restart and fast forward) to this tick.
+    pub seek: Option<u64>,
+}
+
+impl Playback {
+    pub fn new(replay: ReplayFile) -> Self {
+        Playback {
+            replay,
+            next: 0,
+            seek: None,
+        }
+    }
+}
+
+/// Save everything recorded since the scenario started as a replay.
+pub struct SaveReplay {
+    pub path: PathBuf,
+}
+
+/// Start the scenario over from the replay's seed and play its commands back.
+pub struct PlayReplay {
+    pub path: PathBuf,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReplayFile {
+    pub version: u32,
+    pub scenario: String,
+    pub seed: u64,
+    /// The tick the recording was saved on, playback stops here.
+    pub end_tick: u64,
+    pub commands: Vec<TickedCommand>,
+}
+
+impl ReplayFile {
+    /// Only replays recorded by this version, anything else wouldn't play back the same.
+    pub fn read(path: impl AsRef<Path>) -> Option<ReplayFile> {
+        read_ron_with(path, |contents| {
+            let replay: ReplayFile = ron::from_str(contents).map_err(|err| err.to_string())?;
+            if replay.version != REPLAY_VERSION {
+                return Err(format!(
+                    "replay is version {}, this build plays version {REPLAY_VERSION}",
+                    replay.version
+                ));
+            }
+            Ok(replay)
+        })
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> bool {
+        write_ron(path, self)
+    }
+}
@@ -0,0 +1,326 @@
+use bevy::prelude::*;
+
+use crate::{
+    hud::resources::HudFont,
+    save::{components::UnitId, resources::GameSeed},
+    selection::components::{PendingSelection, SelectedUnit},
+    simulation::resources::{SimulationRate, SimulationTick},
+    world_gen::resources::{ActiveScenario, StartScenario},
+};
+
+use super::{components::*, resources::*};
+
+/// Hand this tick the commands given since the last one and record them, or while watching a
+/// replay, hand it the replay's commands for this tick instead.
+pub fn gather_tick_commands(
+    tick: Res<SimulationTick>,
+    mut pending: ResMut<PendingCommands>,
+    mut tick_commands: ResMut<TickCommands>,
+    mut recording: ResMut<Recording>,
+    playback: Option<ResMut<Playback>>,
+) {
+    tick_commands.0.clear();
+
+    let Some(mut playback) = playback else {
+        for command in pending.0.drain(..) {
+            recording.commands.push(TickedCommand {
+                tick: tick.0,
+                command: command.clone(),
+            });
+            tick_commands.0.push(command);
+        }
+        return;
+    };
+
+    // The viewer doesn't get a say in what happens
+    pending.0.clear();
+    let playback = playback.as_mut();
+    while let Some(recorded) = playback.replay.commands.get(playback.next) {
+        if recorded.tick > tick.0 {
+            break;
+        }
+        tick_commands.0.push(recorded.command.clone());
+        playback.next += 1;
+    }
+}
+
+/// Record the selection whenever it changes, so a replay shows what the player was looking at.
+pub fn record_selection(
+    mut pending: ResMut<PendingCommands>,
+    added: Query<(), Added<SelectedUnit>>,
+    mut removed: RemovedComponents<SelectedUnit>,
+    selected: Query<&UnitId, With<SelectedUnit>>,
+) {
+    // Read all of them so they don't count again next frame
+    let removed = removed.iter().count() > 0;
+    if added.is_empty() && !removed {
+        return;
+    }
+
+    let mut units: Vec<UnitId> = selected.iter().copied().collect();
+    units.sort_by_key(|id| id.0);
+    pending.0.push(PlayerCommand::Select(units));
+}
+
+pub fn replay_selection(
+    mut commands: Commands,
+    tick_commands: Res<TickCommands>,
+    selected: Query<(Entity, &SelectedUnit)>,
+    units: Query<(Entity, &UnitId)>,
+) {
+    // Only the last selection this tick matters
+    let last_selection = tick_commands
+        .0
+        .iter()
+        .rev()
+        .find_map(|command| match command {
+            PlayerCommand::Select(ids) => Some(ids),
+            _ => None,
+        });
+    let Some(ids) = last_selection else { return; };
+
+    for (entity, selected) in selected.iter() {
+        selected.clear(&mut commands, entity);
+    }
+    for (entity, id) in units.iter() {
+        if ids.contains(id) {
+            commands.entity(entity).insert(PendingSelection);
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn replay_hotkeys(
+    mut commands: Commands,
+    input_keys: Res<Input<KeyCode>>,
+    scenario: Res<ActiveScenario>,
+    tick: Res<SimulationTick>,
+    mut recording: ResMut<Recording>,
+    mut rate: ResMut<SimulationRate>,
+    playback: Option<Res<Playback>>,
+    mut saves: EventWriter<SaveReplay>,
+    mut plays: EventWriter<PlayReplay>,
+) {
+    let path = scenario.save_dir().join(REPLAY_FILE);
+    if input_keys.just_pressed(SAVE_REPLAY_KEY) {
+        saves.send(SaveReplay { path });
+    } else if input_keys.just_pressed(PLAY_REPLAY_KEY) {
+        let Some(playback) = playback else {
+            plays.send(PlayReplay { path });
+            return;
+        };
+
+        // Take over from here, what's been played so far becomes the start of our own recording
+        *recording = Recording {
+            seed: playback.replay.seed,
+            commands: playback
+                .replay
+                .commands
+                .iter()
+                .filter(|recorded| recorded.tick <= tick.0)
+                .cloned()
+                .collect(),
+            replayable: true,
+        };
+        *rate = SimulationRate::default();
+        commands.remove_resource::<Playback>();
+        info!("Stopped replay at tick {}", tick.0);
+    }
+}
+
+pub fn save_replay(
+    mut saves: EventReader<SaveReplay>,
+    scenario: Res<ActiveScenario>,
+    tick: Res<SimulationTick>,
+    recording: Res<Recording>,
+    playback: Option<Res<Playback>>,
+) {
+    for save in saves.iter() {
+        if playback.is_some() {
+            warn!("Can't save a replay while watching one");
+            continue;
+        }
+        if !recording.replayable {
+            warn!("A save was loaded since the scenario started, there's nothing to replay from");
+            continue;
+        }
+
+        let replay = ReplayFile {
+            version: REPLAY_VERSION,
+            scenario: scenario.name.clone(),
+            seed: recording.seed,
+            end_tick: tick.0,
+            commands: recording.commands.clone(),
+        };
+        if replay.write(&save.path) {
+            info!("Saved replay to {:?}", save.path);
+        }
+    }
+}
+
+pub fn play_replay(
+    mut commands: Commands,
+    mut plays: EventReader<PlayReplay>,
+    mut seed: ResMut<GameSeed>,
+    mut rate: ResMut<SimulationRate>,
+    active: Res<ActiveScenario>,
+    mut scenario: EventWriter<StartScenario>,
+) {
+    let Some(play) = plays.iter().last() else { return; };
+    let Some(replay) = ReplayFile::read(&play.path) else { return; };
+    if replay.scenario != active.name {
+        warn!(
+            "Replay was recorded in {} but this is {}, it won't play back the same",
+            replay.scenario, active.name
+        );
+    }
+
+    info!(
+        "Playing replay {:?} from tick 0 to {}",
+        play.path, replay.end_tick
+    );
+    seed.0 = replay.seed;
+    *rate = SimulationRate::default();
+    commands.insert_resource(Playback::new(replay));
+    scenario.send(StartScenario);
+}
+
+/// Every fresh start of the scenario starts a fresh recording, or plays the replay from the top.
+pub fn reset_recording(
+    seed: Res<GameSeed>,
+    mut recording: ResMut<Recording>,
+    playback: Option<ResMut<Playback>>,
+) {
+    *recording = Recording {
+        seed: seed.0,
+        commands: vec![],
+        replayable: true,
+    };
+    if let Some(mut playback) = playback {
+        playback.next = 0;
+    }
+}
+
+/// Loading a save stops a replay, and means our recording no longer follows from the seed.
+pub fn forget_recording_on_load(
+    mut commands: Commands,
+    mut recording: ResMut<Recording>,
+    mut rate: ResMut<SimulationRate>,
+    playback: Option<Res<Playback>>,
+) {
+    recording.replayable = false;
+    if playback.is_some() {
+        commands.remove_resource::<Playback>();
+        *rate = SimulationRate::default();
+    }
+}
+
+pub fn playback_controls(
+    input_keys: Res<Input<KeyCode>>,
+    tick: Res<SimulationTick>,
+    mut playback: ResMut<Playback>,
+) {
+    let end = playback.replay.end_tick;
+    if input_keys.just_pressed(REPLAY_RESTART_KEY) {
+        playback.seek = Some(0);
+    } else if input_keys.just_pressed(REPLAY_BACK_KEY) {
+        playback.seek = Some(tick.0.saturating_sub(REPLAY_SKIP_TICKS));
+    } else if input_keys.just_pressed(REPLAY_FORWARD_KEY) {
+        playback.seek = Some((tick.0 + REPLAY_SKIP_TICKS).min(end));
+    }
+}
+
+/// Jump to `Playback::seek`. Going back means starting over from the seed, then both ways it's
+/// running ticks as fast as we can until we get there.
+pub fn seek_replay(world: &mut World) {
+    let Some(target) = world
+        .get_resource::<Playback>()
+        .and_then(|playback| playback.seek)
+    else {
+        return;
+    };
+    let tick = world.resource::<SimulationTick>().0;
+
+    if target < tick {
+        world.send_event(StartScenario);
+        return;
+    }
+
+    let steps = (target - tick).min(MAX_SEEK_TICKS_PER_FRAME as u64);
+    for _ in 0..steps {
+        world.run_schedule(CoreSchedule::FixedUpdate);
+    }
+
+    if world.resource::<SimulationTick>().0 >= target {
+        world.resource_mut::<Playback>().seek = None;
+    }
+}
+
+/// Back at the main menu, the next game is played rather than watched.
+pub fn stop_replay(mut commands: Commands, mut rate: ResMut<SimulationRate>) {
+    commands.remove_resource::<Playback>();
+    *rate = SimulationRate::default();
+}
+
+/// Stop at the end of the recording, there's nothing after it to play.
+pub fn finish_replay(
+    tick: Res<SimulationTick>,
+    playback: Res<Playback>,
+    mut rate: ResMut<SimulationRate>,
+) {
+    if tick.0 >= playback.replay.end_tick && !rate.paused {
+        rate.paused = true;
+        info!("Replay finished at tick {}", tick.0);
+    }
+}
+
+pub fn spawn_replay_status(mut commands: Commands, font: Res<HudFont>) {
+    commands
+        .spawn(
+            TextBundle::from_section("", font.style(16.0)).with_style(Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    left: Val::Px(10.0),
+                    top: Val::Px(10.0),
+                    ..default()
+                },
+                ..default()
+            }),
+        )
+        .insert(ReplayStatusText)
+        .insert(Name::new("Replay Status"));
+}
+
+pub fn update_replay_status(
+    tick: Res<SimulationTick>,
+    rate: Res<SimulationRate>,
+    playback: Option<Res<Playback>>,
+    mut text: Query<(&mut Text, &mut Visibility), With<ReplayStatusText>>,
+) {
+    let Ok((mut text, mut visibility)) = text.get_single_mut() else { return; };
+    let Some(playback) = playback else {
+        if *visibility != Visibility::Hidden {
+            *visibility = Visibility::Hidden;
+        }
+        return;
+    };
+
+    if *visibility != Visibility::Inherited {
+        *visibility = Visibility::Inherited;
+    }
+    let state = if playback.seek.is_some() {
+        "seeking"
+    } else if rate.paused {
+        "paused"
+    } else {
+        "playing"
+    };
+    let value = format!(
+        "Replay {state}  tick {} / {}  {}x\n\
+         Space pause  [ ] speed  Home restart  PgUp/PgDn skip  F7 take over",
+        tick.0, playback.replay.end_tick, rate.speed
+    );
+    if text.sections[0].value != value {
+        text.sections[0].value = value;
+    }
+}
@@ -0,0 +1,7 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Stable id for a unit, entities get new ids every time a save is loaded so anything in a save
+/// that points at another unit (attack targets, the selection) uses this instead.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct UnitId(pub u32);
@@ -0,0 +1,80 @@
+use std::path::Path;
+
+use bevy::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
+
+pub mod components;
+pub mod resources;
+mod systems;
+
+use crate::game_state::GameState;
+use resources::*;
+use systems::*;
+
+/// Quick save (F5) and quick load (F9) of the whole game into the scenario's save folder, only
+/// while playing.
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UnitIds>()
+            .init_resource::<SaveResults>()
+            .add_event::<SaveGame>()
+            .add_event::<LoadGame>()
+            .add_system(save_hotkeys.run_if(in_state(GameState::InGame)))
+            .add_system(save_game.after(save_hotkeys))
+            .add_system(load_game.after(save_hotkeys));
+    }
+}
+
+/// Write `value` out as pretty ron, making its folder if it isn't there yet. Anything going wrong
+/// is logged, all that comes back is whether it was written.
+pub fn write_ron(path: impl AsRef<Path>, value: &impl Serialize) -> bool {
+    let path = path.as_ref();
+    let contents = match ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default()) {
+        Ok(contents) => contents,
+        Err(err) => {
+            error!("Failed to serialize {path:?}: {err}");
+            return false;
+        }
+    };
+
+    if let Some(dir) = path.parent() {
+        if let Err(err) = std::fs::create_dir_all(dir) {
+            error!("Failed to create {dir:?}: {err}");
+            return false;
+        }
+    }
+    if let Err(err) = std::fs::write(path, contents) {
+        error!("Failed to write {path:?}: {err}");
+        return false;
+    }
+    true
+}
+
+/// Read a ron file straight into `T`, logging why if it can't.
+pub fn read_ron<T: DeserializeOwned>(path: impl AsRef<Path>) -> Option<T> {
+    read_ron_with(path, |contents| {
+        ron::from_str(contents).map_err(|err| err.to_string())
+    })
+}
+
+/// Read a file and hand it to `parse`, for anything that has to look at it before it knows what
+/// it's reading (like which version a save is).
+pub fn read_ron_with<T>(
+    path: impl AsRef<Path>,
+    parse: impl FnOnce(&str) -> Result<T, String>,
+) -> Option<T> {
+    let path = path.as_ref();
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) => {
+            error!("Failed to read {path:?}: {err}");
+            return None;
+        }
+    };
+
+    parse(&contents)
+        .map_err(|err| error!("Failed to load {path:?}: {err}"))
+        .ok()
+}
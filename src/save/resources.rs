@@ -0,0 +1,184 @@
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use bevy_turborand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{components::UnitId, read_ron_with, write_ron};
+use crate::{camera::components::OrbitPose, unit_render::components::UnitArchetype};
+
+/// Bump this whenever `SaveFile` changes shape, and add a migration from the old version.
+pub const SAVE_VERSION: u32 = 3;
+
+/// F5/F9 save to and load from this file in the scenario's save folder.
+pub const QUICKSAVE_FILE: &str = "quicksave.ron";
+
+pub const QUICKSAVE_KEY: KeyCode = KeyCode::F5;
+pub const QUICKLOAD_KEY: KeyCode = KeyCode::F9;
+
+/// The seed the global RNG was started with, so a save can put it back.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct GameSeed(pub u64);
+
+/// Hands out `UnitId`s, saved along with the units so ids are never reused after a load.
+#[derive(Resource, Default)]
+pub struct UnitIds {
+    pub next: u32,
+}
+
+impl UnitIds {
+    pub fn allocate(&mut self) -> UnitId {
+        let id = UnitId(self.next);
+        self.next += 1;
+        id
+    }
+}
+
+/// Write the current game to `path`.
+pub struct SaveGame {
+    pub path: PathBuf,
+}
+
+/// Throw away the current game and replace it with the one saved at `path`.
+pub struct LoadGame {
+    pub path: PathBuf,
+}
+
+/// Whether the last save and load actually happened, for anything that has to know rather than
+/// just read the log.
+#[derive(Resource, Default)]
+pub struct SaveResults {
+    pub saved: Option<bool>,
+    pub loaded: Option<bool>,
+}
+
+/// Everything written to a save file.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SaveFile {
+    pub version: u32,
+    pub seed: u64,
+    /// Where the global RNG had got to, so a loaded game rolls the same numbers it would have.
+    pub rng: GlobalRng,
+    /// Simulation tick the game was saved on.
+    pub tick: u64,
+    pub next_unit_id: u32,
+    pub camera: Option<OrbitPose>,
+    pub selected: Vec<UnitId>,
+    pub units: Vec<SavedUnit>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SavedUnit {
+    pub id: UnitId,
+    pub name: String,
+    pub archetype: UnitArchetype,
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub health: Option<SavedHealth>,
+    #[serde(default)]
+    pub orders: SavedOrders,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct SavedHealth {
+    pub current: f32,
+    pub max: f32,
+}
+
+/// A unit can have several of these at once (patrolling units are also walking somewhere), so
+/// they're saved as they are rather than as a single `OrderKind`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SavedOrders {
+    pub walk_to: Option<Vec3>,
+    pub patrol: Option<(Vec3, Vec3)>,
+    pub hold: bool,
+    pub attack: Option<UnitId>,
+}
+
+/// Just enough of a save to know which version of `SaveFile` the rest of it is.
+#[derive(Deserialize)]
+struct SaveHeader {
+    version: u32,
+}
+
+impl SaveFile {
+    pub fn read(path: impl AsRef<Path>) -> Option<SaveFile> {
+        read_ron_with(path, |contents| {
+            let header: SaveHeader = ron::from_str(contents).map_err(|err| err.to_string())?;
+            migrate(header.version, contents)
+        })
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> bool {
+        write_ron(path, self)
+    }
+}
+
+/// Parse a save of any version we know about into the current `SaveFile`. Older versions keep
+/// their own schema struct and get converted up one version at a time until they're current.
+fn migrate(version: u32, contents: &str) -> Result<SaveFile, String> {
+    match version {
+        SAVE_VERSION => ron::from_str(contents).map_err(|err| err.to_string()),
+        2 => ron::from_str::<SaveFileV2>(contents)
+            .map(SaveFile::from)
+            .map_err(|err| err.to_string()),
+        1 => ron::from_str::<SaveFileV1>(contents)
+            .map(SaveFileV2::from)
+            .map(SaveFile::from)
+            .map_err(|err| err.to_string()),
+        newer if newer > SAVE_VERSION => Err(format!(
+            "save version {newer} is newer than this build understands ({SAVE_VERSION})"
+        )),
+        older => Err(format!("no migration from save version {older}")),
+    }
+}
+
+/// Saves from before the simulation counted ticks.
+#[derive(Deserialize)]
+struct SaveFileV1 {
+    seed: u64,
+    next_unit_id: u32,
+    camera: Option<OrbitPose>,
+    selected: Vec<UnitId>,
+    units: Vec<SavedUnit>,
+}
+
+impl From<SaveFileV1> for SaveFileV2 {
+    fn from(old: SaveFileV1) -> Self {
+        SaveFileV2 {
+            seed: old.seed,
+            tick: 0,
+            next_unit_id: old.next_unit_id,
+            camera: old.camera,
+            selected: old.selected,
+            units: old.units,
+        }
+    }
+}
+
+/// Saves from before the RNG's state was kept.
+#[derive(Deserialize)]
+struct SaveFileV2 {
+    seed: u64,
+    tick: u64,
+    next_unit_id: u32,
+    camera: Option<OrbitPose>,
+    selected: Vec<UnitId>,
+    units: Vec<SavedUnit>,
+}
+
+impl From<SaveFileV2> for SaveFile {
+    fn from(old: SaveFileV2) -> Self {
+        SaveFile {
+            version: SAVE_VERSION,
+            seed: old.seed,
+            // Best we can do, it's where loading these used to leave the RNG
+            rng: GlobalRng::with_seed(old.seed),
+            tick: old.tick,
+            next_unit_id: old.next_unit_id,
+            camera: old.camera,
+            selected: old.selected,
+            units: old.units,
+        }
+    }
+}
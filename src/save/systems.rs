@@ -0,0 +1,226 @@
+use bevy::{prelude::*, utils::HashMap};
+use bevy_turborand::prelude::*;
+
+use crate::{
+    ai::ENEMY_BEHAVIOUR_PATH,
+    camera::{
+        resources::{CameraJump, CameraRig, CameraRigs},
+        PanOrbitCamera,
+    },
+    combat::components::{AttackTarget, Health},
+    orders::components::{HoldPosition, Patrol},
+    selection::{
+        components::{PendingSelection, SelectedUnit},
+        resources::Hovered,
+    },
+    simulation::{components::InterpolatedTransform, resources::SimulationTick},
+    unit_render::{
+        components::UnitArchetype,
+        resources::{UnitRenderCache, UnitRenderSettings},
+    },
+    units::{components::WalkToLocation, spawn::spawn_unit},
+    world_gen::resources::ActiveScenario,
+};
+
+use super::{components::*, resources::*};
+
+pub fn save_hotkeys(
+    input_keys: Res<Input<KeyCode>>,
+    scenario: Res<ActiveScenario>,
+    mut saves: EventWriter<SaveGame>,
+    mut loads: EventWriter<LoadGame>,
+) {
+    let path = scenario.save_dir().join(QUICKSAVE_FILE);
+    if input_keys.just_pressed(QUICKSAVE_KEY) {
+        saves.send(SaveGame { path });
+    } else if input_keys.just_pressed(QUICKLOAD_KEY) {
+        loads.send(LoadGame { path });
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn save_game(
+    mut saves: EventReader<SaveGame>,
+    seed: Res<GameSeed>,
+    rng: Res<GlobalRng>,
+    tick: Res<SimulationTick>,
+    unit_ids: Res<UnitIds>,
+    rigs: Res<CameraRigs>,
+    pan_orbit: Query<&PanOrbitCamera>,
+    ids: Query<&UnitId>,
+    units: Query<(
+        &UnitId,
+        &UnitArchetype,
+        Option<&Name>,
+        &InterpolatedTransform,
+        Option<&Health>,
+        Option<&WalkToLocation>,
+        Option<&Patrol>,
+        Option<&HoldPosition>,
+        Option<&AttackTarget>,
+    )>,
+    selected: Query<&UnitId, With<SelectedUnit>>,
+    mut results: ResMut<SaveResults>,
+) {
+    for save in saves.iter() {
+        let units = units
+            .iter()
+            .map(
+                |(id, archetype, name, transform, health, walk_to, patrol, hold, attack)| {
+                    SavedUnit {
+                        id: *id,
+                        name: name.map(|name| name.to_string()).unwrap_or_default(),
+                        archetype: *archetype,
+                        // Where the simulation has it, not where it's drawn between ticks
+                        translation: transform.current.translation,
+                        rotation: transform.current.rotation,
+                        health: health.map(|health| SavedHealth {
+                            current: health.current,
+                            max: health.max,
+                        }),
+                        orders: SavedOrders {
+                            walk_to: walk_to.map(|walk_to| walk_to.0),
+                            patrol: patrol.map(|patrol| (patrol.from, patrol.to)),
+                            hold: hold.is_some(),
+                            // Targets that aren't units (or are already gone) aren't worth keeping
+                            attack: attack.and_then(|attack| ids.get(attack.0).ok().copied()),
+                        },
+                    }
+                },
+            )
+            .collect();
+
+        let save_file = SaveFile {
+            version: SAVE_VERSION,
+            seed: seed.0,
+            rng: rng.clone(),
+            tick: tick.0,
+            next_unit_id: unit_ids.next,
+            // Whichever rig is active, the orbit camera's pose is what we come back to
+            camera: pan_orbit
+                .get_single()
+                .map(|pan_orbit| pan_orbit.pose())
+                .ok()
+                .or(rigs.parked),
+            selected: selected.iter().copied().collect(),
+            units,
+        };
+
+        let saved = save_file.write(&save.path);
+        if saved {
+            info!("Saved game to {:?}", save.path);
+        }
+        results.saved = Some(saved);
+    }
+}
+
+/// Despawn every unit and spawn the saved ones in their place. AI memory isn't saved, enemies
+/// start their behaviour trees from the top again.
+#[allow(clippy::too_many_arguments)]
+pub fn load_game(
+    mut commands: Commands,
+    mut loads: EventReader<LoadGame>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut render_cache: ResMut<UnitRenderCache>,
+    render_settings: Res<UnitRenderSettings>,
+    asset_server: Res<AssetServer>,
+    mut seed: ResMut<GameSeed>,
+    mut tick: ResMut<SimulationTick>,
+    mut rng: ResMut<GlobalRng>,
+    mut unit_ids: ResMut<UnitIds>,
+    mut rigs: ResMut<CameraRigs>,
+    mut hovered: ResMut<Hovered>,
+    mut jumps: EventWriter<CameraJump>,
+    existing: Query<Entity, With<UnitId>>,
+    mut results: ResMut<SaveResults>,
+) {
+    let Some(load) = loads.iter().last() else { return; };
+    let save_file = SaveFile::read(&load.path);
+    results.loaded = Some(save_file.is_some());
+    let Some(save_file) = save_file else { return; };
+
+    for entity in existing.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    // The hover ring was a child of one of the units we just despawned
+    *hovered = Hovered::default();
+
+    seed.0 = save_file.seed;
+    *rng = save_file.rng.clone();
+    tick.0 = save_file.tick;
+    unit_ids.next = save_file.next_unit_id;
+
+    let enemy_behaviour = asset_server.load(ENEMY_BEHAVIOUR_PATH);
+    let enemies = save_file
+        .units
+        .iter()
+        .filter(|unit| unit.archetype == UnitArchetype::Enemy)
+        .count();
+    let instance_enemies = enemies >= render_settings.instancing_threshold;
+
+    let mut spawned = HashMap::new();
+    for unit in save_file.units.iter() {
+        let entity = spawn_unit(
+            &mut commands,
+            &mut render_cache,
+            &mut meshes,
+            &mut materials,
+            unit.id,
+            unit.name.clone(),
+            unit.archetype,
+            instance_enemies && unit.archetype == UnitArchetype::Enemy,
+            unit.translation,
+            0.0,
+            &enemy_behaviour,
+        );
+        spawned.insert(unit.id, entity);
+
+        let mut entity = commands.entity(entity);
+        let transform = Transform::from_translation(unit.translation).with_rotation(unit.rotation);
+        entity
+            .insert(transform)
+            .insert(InterpolatedTransform::from(transform));
+        if let Some(health) = unit.health {
+            entity.insert(Health {
+                current: health.current,
+                max: health.max,
+            });
+        }
+
+        let orders = &unit.orders;
+        if let Some(location) = orders.walk_to {
+            entity.insert(WalkToLocation(location));
+        }
+        if let Some((from, to)) = orders.patrol {
+            entity.insert(Patrol { from, to });
+        }
+        if orders.hold {
+            entity.insert(HoldPosition);
+        }
+    }
+
+    // Now every unit has an entity, point attacks at their targets
+    for unit in save_file.units.iter() {
+        let Some(target) = unit.orders.attack.and_then(|target| spawned.get(&target)) else { continue; };
+        commands
+            .entity(spawned[&unit.id])
+            .insert(AttackTarget(*target));
+    }
+
+    for id in save_file.selected.iter() {
+        if let Some(entity) = spawned.get(id) {
+            commands.entity(*entity).insert(PendingSelection);
+        }
+    }
+
+    if let Some(pose) = save_file.camera {
+        if rigs.active == CameraRig::PanOrbit {
+            jumps.send(CameraJump::to_pose(pose));
+        } else {
+            rigs.parked = Some(pose);
+        }
+    }
+
+    info!("Loaded game from {:?}", load.path);
+}
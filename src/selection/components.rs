@@ -8,3 +8,37 @@ pub struct PendingSelection;
 
 #[derive(Component)]
 pub struct SelectedUnit(pub Entity);
+
+impl SelectedUnit {
+    /// Deselect the unit and get rid of its selection ring.
+    pub fn clear(&self, commands: &mut Commands, unit: Entity) {
+        if let Some(mut entity) = commands.get_entity(unit) {
+            entity.remove::<SelectedUnit>();
+        }
+        if let Some(ring) = commands.get_entity(self.0) {
+            ring.despawn_recursive();
+        }
+    }
+}
+
+/// The selection ring under the unit the cursor is over.
+#[derive(Component)]
+pub struct HoverRing;
+
+/// How a unit relates to the player, picks the colour of its rings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Relationship {
+    Friendly,
+    Hostile,
+    Neutral,
+}
+
+impl Relationship {
+    pub fn of(player: bool, enemy: bool) -> Relationship {
+        match (player, enemy) {
+            (true, _) => Relationship::Friendly,
+            (_, true) => Relationship::Hostile,
+            _ => Relationship::Neutral,
+        }
+    }
+}
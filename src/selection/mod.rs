@@ -2,12 +2,12 @@ use bevy::{
     input::mouse::{MouseButtonInput, MouseMotion},
     prelude::*,
 };
-use bevy_polyline::prelude::*;
 
 pub mod components;
-mod resources;
+pub mod resources;
 mod systems;
 
+use crate::{game_state::GameState, intent::InputStage};
 use resources::*;
 use systems::*;
 //use components::*;
@@ -17,12 +17,16 @@ pub struct SelectionPlugin;
 impl Plugin for SelectionPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(Selecting::default())
-            .add_plugin(PolylinePlugin)
+            .init_resource::<SelectionAssets>()
+            .init_resource::<Hovered>()
             .add_system(
                 handle_mouse_input_selection
+                    .in_set(InputStage::World)
                     .run_if(on_event::<MouseMotion>().or_else(on_event::<MouseButtonInput>())),
             )
             .add_system(draw_selection_indicator)
-            .add_system(draw_selection_box);
+            .add_system(update_hovered_unit.after(InputStage::UiBlocking))
+            .add_system(draw_selection_box.after(handle_mouse_input_selection))
+            .add_system(clear_selecting.in_schedule(OnEnter(GameState::MainMenu)));
     }
 }
@@ -1,6 +1,8 @@
 use bevy::prelude::*;
 use bevy_polyline::prelude::*;
 
+use super::components::Relationship;
+
 #[derive(Resource, Default)]
 pub struct Selecting {
     pub first_entity: Option<Entity>,
@@ -9,3 +11,62 @@ pub struct Selecting {
     pub picking_mesh: Option<Handle<Polyline>>,
     pub picking_box: Option<Entity>,
 }
+
+/// Meshes and materials for the selection rings, made once and shared by every ring.
+/// The ring mesh has a radius of 1 and gets scaled to fit each unit.
+#[derive(Resource)]
+pub struct SelectionAssets {
+    pub ring: Handle<Mesh>,
+    pub friendly: Handle<StandardMaterial>,
+    pub hostile: Handle<StandardMaterial>,
+    pub neutral: Handle<StandardMaterial>,
+    pub hover: Handle<StandardMaterial>,
+}
+
+impl SelectionAssets {
+    pub fn material(&self, relationship: Relationship) -> Handle<StandardMaterial> {
+        match relationship {
+            Relationship::Friendly => self.friendly.clone(),
+            Relationship::Hostile => self.hostile.clone(),
+            Relationship::Neutral => self.neutral.clone(),
+        }
+    }
+}
+
+impl FromWorld for SelectionAssets {
+    fn from_world(world: &mut World) -> Self {
+        let ring = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(Mesh::from(shape::Torus {
+                radius: 1.0,
+                ring_radius: 0.1,
+                subdivisions_segments: 40,
+                subdivisions_sides: 6,
+            }));
+
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        let mut ring_material = |color: Color| {
+            materials.add(StandardMaterial {
+                base_color: color,
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                ..default()
+            })
+        };
+
+        SelectionAssets {
+            ring,
+            friendly: ring_material(Color::rgba(0.1, 0.9, 0.2, 0.7)),
+            hostile: ring_material(Color::rgba(0.9, 0.1, 0.1, 0.7)),
+            neutral: ring_material(Color::rgba(0.9, 0.8, 0.1, 0.7)),
+            hover: ring_material(Color::rgba(1.0, 1.0, 1.0, 0.4)),
+        }
+    }
+}
+
+/// The selectable unit under the cursor, and the ring we put under it.
+#[derive(Resource, Default)]
+pub struct Hovered {
+    pub entity: Option<Entity>,
+    pub ring: Option<Entity>,
+}
@@ -1,11 +1,18 @@
 use bevy::{prelude::*, window::PrimaryWindow};
 use bevy_polyline::prelude::*;
-use bevy_rapier3d::prelude::RapierContext;
+use bevy_rapier3d::prelude::{QueryFilter, RapierContext};
 
-use crate::{camera::components::PlayerCamera, line_drawing::Square, screen_ray_to_entity};
+use crate::{
+    camera::{components::PlayerCamera, screen_ray_to_entity},
+    intent::resources::PointerIntents,
+    line_drawing::{Draped, Square},
+    units::components::{Enemy, Player, UnitSize},
+    world_gen::resources::Ground,
+};
 
 use super::{components::*, resources::*};
 
+#[allow(clippy::too_many_arguments)]
 pub fn handle_mouse_input_selection(
     mut commands: Commands,
     mouse_btn: Res<Input<MouseButton>>,
@@ -16,7 +23,13 @@ pub fn handle_mouse_input_selection(
     previous_sel_entities: Query<(Entity, &SelectedUnit)>,
     selectable: Query<(Entity, &GlobalTransform), With<Selectable>>,
     camera: Query<(&Camera, &GlobalTransform), With<PlayerCamera>>,
+    intents: Res<PointerIntents>,
 ) {
+    // The UI (minimap etc.) has the mouse this frame
+    if intents.is_claimed(MouseButton::Left) {
+        return;
+    }
+
     // Guarded early returns if we don't have a cursor or camera
     let Ok(window) = windows.get_single() else { return; };
     let Some(cursor_position) = window.cursor_position() else { return; };
@@ -103,62 +116,137 @@ pub fn handle_mouse_input_selection(
             commands.get_entity(pickingbox).unwrap().despawn_recursive();
         }
 
-        commands.insert_resource(Selecting::default());
+        // Straight away rather than through commands, or the box would be drawn again this frame
+        *selecting = Selecting::default();
     }
 }
 
+#[allow(clippy::type_complexity)]
 pub fn draw_selection_indicator(
     mut commands: Commands,
-    query: Query<Entity, With<PendingSelection>>,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
+    assets: Res<SelectionAssets>,
+    query: Query<
+        (
+            Entity,
+            Option<&UnitSize>,
+            Option<&Player>,
+            Option<&Enemy>,
+            Option<&SelectedUnit>,
+        ),
+        With<PendingSelection>,
+    >,
 ) {
-    let material = materials.add(StandardMaterial {
-        base_color: Color::rgba(0.0, 0.0, 0.0, 0.4),
-        alpha_mode: AlphaMode::Blend,
-        ..default()
-    });
-
-    let mesh = meshes.add(Mesh::from(shape::Torus {
-        radius: 1.0,
-        ring_radius: 0.1,
-        subdivisions_segments: 40,
-        subdivisions_sides: 6,
-    }));
-
-    for e in query.iter() {
+    for (e, size, player, enemy, selected) in query.iter() {
+        commands.entity(e).remove::<PendingSelection>();
+        // Shift selecting something that's already selected, it already has a ring
+        if selected.is_some() {
+            continue;
+        }
+
         let selection_icon_entity = commands
             .spawn(PbrBundle {
-                mesh: mesh.clone(),
-                transform: Transform::from_xyz(0.0, 0.0, 0.0),
-                material: material.clone(),
+                mesh: assets.ring.clone(),
+                transform: ring_transform(size, 1.0),
+                material: assets.material(Relationship::of(player.is_some(), enemy.is_some())),
                 ..default()
             })
             .id();
 
-        let mut ec = commands.get_entity(e).unwrap();
-        ec.remove::<PendingSelection>();
+        let mut ec = commands.entity(e);
         ec.add_child(selection_icon_entity);
         ec.insert(SelectedUnit(selection_icon_entity));
     }
 }
 
+/// Put a faint ring under whatever selectable unit the cursor is over. This is our own raycast
+/// rather than bevy_mod_picking's highlighting, instanced units don't have a mesh for it to pick.
+#[allow(clippy::too_many_arguments)]
+pub fn update_hovered_unit(
+    mut commands: Commands,
+    mut hovered: ResMut<Hovered>,
+    assets: Res<SelectionAssets>,
+    intents: Res<PointerIntents>,
+    rapier_context: Res<RapierContext>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    camera: Query<(&Camera, &GlobalTransform), With<PlayerCamera>>,
+    selectable: Query<Option<&UnitSize>, With<Selectable>>,
+) {
+    let cursor = windows.get_single().ok().and_then(|w| w.cursor_position());
+    let under_cursor = match (cursor, camera.get_single()) {
+        // Nothing in the world is hovered while the cursor is over the UI
+        (Some(cursor), Ok((camera, camera_location))) if !intents.is_claimed(MouseButton::Left) => {
+            screen_ray_to_entity(camera, &rapier_context, camera_location, cursor)
+                .map(|(entity, _)| entity)
+                .filter(|entity| selectable.contains(*entity))
+        }
+        _ => None,
+    };
+
+    if under_cursor == hovered.entity {
+        return;
+    }
+
+    // The unit might have died and taken the ring with it
+    if let Some(mut ring) = hovered.ring.and_then(|ring| commands.get_entity(ring)) {
+        ring.despawn();
+    }
+    hovered.ring = None;
+    hovered.entity = under_cursor;
+
+    let Some(entity) = under_cursor else { return; };
+    let Ok(size) = selectable.get(entity) else { return; };
+    let ring = commands
+        .spawn(PbrBundle {
+            mesh: assets.ring.clone(),
+            // A little bigger so it still shows around a selection ring
+            transform: ring_transform(size, 1.15),
+            material: assets.hover.clone(),
+            ..default()
+        })
+        .insert(HoverRing)
+        .id();
+    commands.entity(entity).add_child(ring);
+    hovered.ring = Some(ring);
+}
+
+/// Size the ring to go around the unit's footprint and sit it on the ground under the unit.
+fn ring_transform(size: Option<&UnitSize>, scale: f32) -> Transform {
+    let (footprint, height) = size
+        .map(|size| (size.collider, size.model))
+        .unwrap_or((1.0, 0.0));
+    // Far enough out to clear the corners of a square footprint, with a little gap
+    let radius = footprint * std::f32::consts::FRAC_1_SQRT_2 + 0.1;
+
+    Transform::from_xyz(0.0, -height / 2.0 + 0.02, 0.0).with_scale(Vec3::splat(radius * scale))
+}
+
 pub fn draw_selection_box(
     mut commands: Commands,
     mut selecting: ResMut<Selecting>,
     mut polyline_materials: ResMut<Assets<PolylineMaterial>>,
     mut polylines: ResMut<Assets<Polyline>>,
+    rapier_context: Res<RapierContext>,
+    ground: Res<Ground>,
 ) {
     if selecting.first_click == selecting.last_click {
         return;
     }
-    // TODO: Replace pin to ground ith a projected test to draw shorter lines up and down hills.
 
     let mut local_pos = selecting.last_click - selecting.first_click;
     local_pos.y = 0.0;
 
-    let y_offset = 0.02;
-    let poly = Square(local_pos).into();
+    // The box is drawn relative to the first click, so drape it over the ground relative to that too
+    let origin = selecting.first_click;
+    let poly: Polyline = Draped {
+        polyline: Square(local_pos).into(),
+        height: |x, z| {
+            ground_height(&rapier_context, &ground, origin.x + x, origin.z + z)
+                .map_or(0.0, |y| y - origin.y)
+        },
+        max_segment: 0.5,
+        offset: 0.02,
+    }
+    .into();
 
     if let Some(picking_mesh) = &selecting.picking_mesh {
         // We've already created a picking_box so just update it's mesh to the new size
@@ -166,7 +254,7 @@ pub fn draw_selection_box(
         *x = poly;
     } else {
         // This is the first time we've moved, so we need to generate the selection box object
-        let mesh = polylines.add(Square(local_pos).into());
+        let mesh = polylines.add(poly);
         let pickbox = commands
             .spawn(PolylineBundle {
                 polyline: mesh.clone(),
@@ -176,7 +264,7 @@ pub fn draw_selection_box(
                     perspective: true,
                     ..default()
                 }),
-                transform: Transform::from_translation(selecting.first_click + Vec3::Y * y_offset),
+                transform: Transform::from_translation(origin),
                 ..default()
             })
             .insert(Name::new("line"))
@@ -185,3 +273,24 @@ pub fn draw_selection_box(
         selecting.picking_box = Some(pickbox)
     }
 }
+
+/// Height of the ground at x/z, found by casting straight down onto the ground collider so units
+/// standing in the way don't count.
+fn ground_height(rapier_context: &RapierContext, ground: &Ground, x: f32, z: f32) -> Option<f32> {
+    let ground_entity = ground.entity?;
+    let top = 1000.0;
+    let only_ground = |entity| entity == ground_entity;
+    let filter = QueryFilter::default().predicate(&only_ground);
+
+    rapier_context
+        .cast_ray(Vec3::new(x, top, z), Vec3::NEG_Y, f32::MAX, true, filter)
+        .map(|(_, toi)| top - toi)
+}
+
+/// Back at the main menu, a half drawn selection box shouldn't still be there next game.
+pub fn clear_selecting(mut commands: Commands, mut selecting: ResMut<Selecting>) {
+    if let Some(picking_box) = selecting.picking_box {
+        commands.entity(picking_box).despawn_recursive();
+    }
+    *selecting = Selecting::default();
+}
@@ -0,0 +1,18 @@
+use bevy::prelude::*;
+
+/// Where the simulation put this entity on the last two ticks. Gameplay only ever sees
+/// `current`, the `Transform` in between ticks is a blend of the two for drawing.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct InterpolatedTransform {
+    pub previous: Transform,
+    pub current: Transform,
+}
+
+impl From<Transform> for InterpolatedTransform {
+    fn from(transform: Transform) -> Self {
+        InterpolatedTransform {
+            previous: transform,
+            current: transform,
+        }
+    }
+}
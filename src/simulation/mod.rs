@@ -0,0 +1,108 @@
+use bevy::{prelude::*, transform::TransformSystem};
+use bevy_rapier3d::prelude::*;
+
+pub mod components;
+pub mod resources;
+mod systems;
+
+use resources::*;
+use systems::*;
+
+/// Length of one simulation tick in seconds.
+pub const SIMULATION_TICK: f32 = 1.0 / 20.0;
+
+/// The steps of a simulation tick, in order. Everything that changes gameplay state runs in one
+/// of these on the fixed schedule so a tick only ever depends on the last one, never on frame rate.
+#[derive(SystemSet, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SimulationSet {
+    /// Undo the interpolation so the tick starts from the exact simulated state.
+    Restore,
+    /// Collect the player's commands for this tick, live or from a replay.
+    Input,
+    /// Carry out those commands.
+    Commands,
+    Ai,
+    Combat,
+    Movement,
+    /// Keep the new state for interpolating towards, after physics has caught up.
+    Store,
+}
+
+/// Runs gameplay and physics on a fixed timestep and smooths units between ticks for drawing.
+/// Physics has to be added with `with_default_system_setup(false)`, this plugin schedules it.
+pub struct SimulationPlugin;
+
+impl Plugin for SimulationPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(FixedTime::new_from_secs(SIMULATION_TICK))
+            .insert_resource(RapierConfiguration {
+                timestep_mode: TimestepMode::Fixed {
+                    dt: SIMULATION_TICK,
+                    substeps: 1,
+                },
+                ..default()
+            })
+            .init_resource::<SimulationTick>()
+            .init_resource::<SimulationClock>()
+            .init_resource::<SimulationRate>()
+            .edit_schedule(CoreSchedule::FixedUpdate, |schedule| {
+                schedule
+                    .configure_sets(
+                        (
+                            SimulationSet::Restore,
+                            SimulationSet::Input,
+                            SimulationSet::Commands,
+                            SimulationSet::Ai,
+                            SimulationSet::Combat,
+                            SimulationSet::Movement,
+                        )
+                            .chain()
+                            .before(PhysicsSet::SyncBackend),
+                    )
+                    .configure_sets(
+                        (
+                            PhysicsSet::SyncBackend,
+                            PhysicsSet::SyncBackendFlush,
+                            PhysicsSet::StepSimulation,
+                            PhysicsSet::Writeback,
+                        )
+                            .chain(),
+                    )
+                    .configure_set(SimulationSet::Store.after(PhysicsSet::Writeback))
+                    .add_systems(
+                        RapierPhysicsPlugin::<NoUserData>::get_systems(PhysicsSet::SyncBackend)
+                            .in_base_set(PhysicsSet::SyncBackend),
+                    )
+                    .add_systems(
+                        RapierPhysicsPlugin::<NoUserData>::get_systems(
+                            PhysicsSet::SyncBackendFlush,
+                        )
+                        .in_base_set(PhysicsSet::SyncBackendFlush),
+                    )
+                    .add_systems(
+                        RapierPhysicsPlugin::<NoUserData>::get_systems(PhysicsSet::StepSimulation)
+                            .in_base_set(PhysicsSet::StepSimulation),
+                    )
+                    .add_systems(
+                        RapierPhysicsPlugin::<NoUserData>::get_systems(PhysicsSet::Writeback)
+                            .in_base_set(PhysicsSet::Writeback),
+                    );
+            })
+            .add_systems(
+                (advance_tick, restore_simulated_transforms)
+                    .in_set(SimulationSet::Restore)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_system(
+                store_simulated_transforms
+                    .in_set(SimulationSet::Store)
+                    .in_schedule(CoreSchedule::FixedUpdate),
+            )
+            .add_system(apply_simulation_rate.in_base_set(CoreSet::PreUpdate))
+            .add_system(
+                interpolate_transforms
+                    .in_base_set(CoreSet::PostUpdate)
+                    .before(TransformSystem::TransformPropagate),
+            );
+    }
+}
@@ -0,0 +1,78 @@
+use bevy::prelude::*;
+
+use super::SIMULATION_TICK;
+
+/// The speeds the game can be slowed down or sped up to.
+pub const SIMULATION_SPEEDS: [f32; 5] = [0.25, 0.5, 1.0, 2.0, 4.0];
+
+/// How many simulation ticks have run since the game started.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SimulationTick(pub u64);
+
+/// Game time as gameplay sees it. Movement, AI and combat use this rather than `Time`, it moves
+/// on exactly a tick at a time whatever speed the game runs at and not at all while paused.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct SimulationClock {
+    elapsed: f32,
+}
+
+impl Default for SimulationClock {
+    fn default() -> Self {
+        SimulationClock::at_tick(0)
+    }
+}
+
+impl SimulationClock {
+    pub fn at_tick(tick: u64) -> Self {
+        SimulationClock {
+            elapsed: tick as f32 * SIMULATION_TICK,
+        }
+    }
+
+    /// Game time the current tick covers, always the same.
+    pub fn delta_seconds(&self) -> f32 {
+        SIMULATION_TICK
+    }
+
+    /// Game time since the scenario started, up to the end of the current tick.
+    pub fn elapsed_seconds(&self) -> f32 {
+        self.elapsed
+    }
+}
+
+/// How fast ticks run compared to real time. Only changes how often a tick happens, never how
+/// long one is, so the same ticks play out the same at any speed.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct SimulationRate {
+    pub speed: f32,
+    pub paused: bool,
+}
+
+impl Default for SimulationRate {
+    fn default() -> Self {
+        SimulationRate {
+            speed: 1.0,
+            paused: false,
+        }
+    }
+}
+
+impl SimulationRate {
+    /// Up to the next of `SIMULATION_SPEEDS`, staying put at the fastest.
+    pub fn faster(&mut self) {
+        if let Some(speed) = SIMULATION_SPEEDS.iter().find(|speed| **speed > self.speed) {
+            self.speed = *speed;
+        }
+    }
+
+    /// Down to the next of `SIMULATION_SPEEDS`, staying put at the slowest.
+    pub fn slower(&mut self) {
+        if let Some(speed) = SIMULATION_SPEEDS
+            .iter()
+            .rev()
+            .find(|speed| **speed < self.speed)
+        {
+            self.speed = *speed;
+        }
+    }
+}
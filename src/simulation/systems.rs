@@ -0,0 +1,81 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::game_state::GameState;
+
+use super::{components::*, resources::*, SIMULATION_TICK};
+
+/// Ticks only run in the game itself, not the menus, loading or pause screens. Without any
+/// states at all (tests) they always run.
+fn frozen(state: &Option<Res<State<GameState>>>) -> bool {
+    state
+        .as_ref()
+        .is_some_and(|state| state.0 != GameState::InGame)
+}
+
+pub fn advance_tick(mut tick: ResMut<SimulationTick>, mut clock: ResMut<SimulationClock>) {
+    tick.0 += 1;
+    *clock = SimulationClock::at_tick(tick.0);
+}
+
+/// Put back exactly where the last tick left everything, undoing the blend we drew with. The
+/// global transform goes back too, physics syncs its colliders from that.
+pub fn restore_simulated_transforms(
+    mut query: Query<(
+        &mut InterpolatedTransform,
+        &mut Transform,
+        &mut GlobalTransform,
+    )>,
+) {
+    for (mut interpolated, mut transform, mut global) in query.iter_mut() {
+        interpolated.previous = interpolated.current;
+        *transform = interpolated.current;
+        *global = GlobalTransform::from(interpolated.current);
+    }
+}
+
+pub fn store_simulated_transforms(mut query: Query<(&mut InterpolatedTransform, &Transform)>) {
+    for (mut interpolated, transform) in query.iter_mut() {
+        interpolated.current = *transform;
+    }
+}
+
+/// Blend between the last two ticks by how far we are towards the next one.
+pub fn interpolate_transforms(
+    fixed_time: Res<FixedTime>,
+    rate: Res<SimulationRate>,
+    state: Option<Res<State<GameState>>>,
+    mut query: Query<(&InterpolatedTransform, &mut Transform)>,
+) {
+    let t = if rate.paused || frozen(&state) {
+        1.0
+    } else {
+        (fixed_time.accumulated().as_secs_f32() / fixed_time.period.as_secs_f32()).min(1.0)
+    };
+
+    for (interpolated, mut transform) in query.iter_mut() {
+        let (previous, current) = (interpolated.previous, interpolated.current);
+        transform.translation = previous.translation.lerp(current.translation, t);
+        transform.rotation = previous.rotation.slerp(current.rotation, t);
+        transform.scale = previous.scale.lerp(current.scale, t);
+    }
+}
+
+/// Speed up, slow down or stop the fixed timestep. Replacing it also throws away whatever time
+/// had built up, otherwise unpausing would run every tick we missed all at once.
+pub fn apply_simulation_rate(
+    rate: Res<SimulationRate>,
+    state: Option<Res<State<GameState>>>,
+    mut fixed_time: ResMut<FixedTime>,
+) {
+    let period = if rate.paused || rate.speed <= 0.0 || frozen(&state) {
+        // Never reached, so no ticks run
+        Duration::MAX
+    } else {
+        Duration::from_secs_f32(SIMULATION_TICK / rate.speed)
+    };
+    if fixed_time.period != period {
+        *fixed_time = FixedTime::new(period);
+    }
+}
@@ -0,0 +1,47 @@
+use bevy::prelude::*;
+use bytemuck::{Pod, Zeroable};
+use serde::{Deserialize, Serialize};
+
+/// The kinds of unit that look the same, everything of one archetype shares a mesh and material.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum UnitArchetype {
+    Player,
+    Enemy,
+}
+
+impl UnitArchetype {
+    pub fn color(&self) -> Color {
+        match self {
+            UnitArchetype::Player => Color::RED,
+            UnitArchetype::Enemy => Color::WHITE,
+        }
+    }
+}
+
+/// Drawn as part of its archetype's instance batch instead of with its own mesh.
+#[derive(Component)]
+pub struct InstancedUnit;
+
+/// What a unit that just took a hit is drawn in for a moment.
+pub const DAMAGE_FLASH_COLOR: Color = Color::YELLOW;
+
+/// The unit just took damage and is drawn in the flash colour until the timer runs out.
+#[derive(Component)]
+pub struct DamageFlash(pub Timer);
+
+/// One entity per archetype that draws every instanced unit of that archetype in one draw call.
+#[derive(Component)]
+pub struct InstanceBatch(pub UnitArchetype);
+
+#[derive(Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+pub struct InstanceData {
+    pub position: Vec3,
+    pub scale: f32,
+    pub rotation: [f32; 4],
+    pub color: [f32; 4],
+}
+
+/// The instances a batch draws this frame, rebuilt from the units' transforms every frame.
+#[derive(Component, Default, Deref, DerefMut)]
+pub struct InstanceMaterialData(pub Vec<InstanceData>);
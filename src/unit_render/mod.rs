@@ -0,0 +1,24 @@
+use bevy::prelude::*;
+
+pub mod components;
+mod instancing;
+pub mod resources;
+mod systems;
+
+use instancing::InstancedUnitMaterialPlugin;
+use resources::*;
+use systems::*;
+
+/// Shared meshes/materials for units, instanced drawing when there's a lot of them and flashing
+/// units that get hit.
+pub struct UnitRenderPlugin;
+
+impl Plugin for UnitRenderPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UnitRenderSettings>()
+            .init_resource::<UnitRenderCache>()
+            .add_plugin(InstancedUnitMaterialPlugin)
+            .add_systems((start_damage_flashes, end_damage_flashes).chain())
+            .add_system(update_instance_batches.in_base_set(CoreSet::PostUpdate));
+    }
+}
@@ -0,0 +1,52 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use super::components::{UnitArchetype, DAMAGE_FLASH_COLOR};
+
+#[derive(Resource)]
+pub struct UnitRenderSettings {
+    /// Spawning at least this many of an archetype at once draws them instanced instead.
+    pub instancing_threshold: usize,
+}
+
+impl Default for UnitRenderSettings {
+    fn default() -> Self {
+        UnitRenderSettings {
+            instancing_threshold: 200,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct UnitRenderAssets {
+    pub mesh: Handle<Mesh>,
+    pub material: Handle<StandardMaterial>,
+    /// Swapped in while a unit is flashing from damage.
+    pub flash_material: Handle<StandardMaterial>,
+}
+
+/// Meshes and materials shared by every unit of an archetype, made the first time one is spawned.
+#[derive(Resource, Default)]
+pub struct UnitRenderCache {
+    pub assets: HashMap<UnitArchetype, UnitRenderAssets>,
+    pub batches: HashMap<UnitArchetype, Entity>,
+}
+
+impl UnitRenderCache {
+    /// Units of an archetype are all the same size, so only the first one's size is used.
+    pub fn get_or_create(
+        &mut self,
+        archetype: UnitArchetype,
+        size: f32,
+        meshes: &mut Assets<Mesh>,
+        materials: &mut Assets<StandardMaterial>,
+    ) -> UnitRenderAssets {
+        self.assets
+            .entry(archetype)
+            .or_insert_with(|| UnitRenderAssets {
+                mesh: meshes.add(Mesh::from(shape::Cube { size })),
+                material: materials.add(archetype.color().into()),
+                flash_material: materials.add(DAMAGE_FLASH_COLOR.into()),
+            })
+            .clone()
+    }
+}
@@ -0,0 +1,126 @@
+use bevy::{prelude::*, render::view::NoFrustumCulling, utils::HashMap};
+
+use crate::combat::resources::DamageEvent;
+
+use super::{components::*, resources::*};
+
+/// How long a unit stays in the flash colour after being hit.
+const DAMAGE_FLASH_SECONDS: f32 = 0.15;
+
+/// Flash anything that got hit, units drawn on their own swap to the flash material and instanced
+/// ones get the flash colour in their batch.
+#[allow(clippy::type_complexity)]
+pub fn start_damage_flashes(
+    mut commands: Commands,
+    mut damage: EventReader<DamageEvent>,
+    cache: Res<UnitRenderCache>,
+    mut units: Query<(
+        &UnitArchetype,
+        Option<&mut DamageFlash>,
+        Option<&mut Handle<StandardMaterial>>,
+    )>,
+) {
+    for event in damage.iter() {
+        let Ok((archetype, flash, material)) = units.get_mut(event.target) else { continue; };
+        // Hit again mid flash, just start it over
+        if let Some(mut flash) = flash {
+            flash.0.reset();
+            continue;
+        }
+
+        commands
+            .entity(event.target)
+            .insert(DamageFlash(Timer::from_seconds(
+                DAMAGE_FLASH_SECONDS,
+                TimerMode::Once,
+            )));
+        if let (Some(mut material), Some(assets)) = (material, cache.assets.get(archetype)) {
+            *material = assets.flash_material.clone();
+        }
+    }
+}
+
+/// Put units back to their archetype's look once their flash is over.
+pub fn end_damage_flashes(
+    mut commands: Commands,
+    time: Res<Time>,
+    cache: Res<UnitRenderCache>,
+    mut flashing: Query<(
+        Entity,
+        &UnitArchetype,
+        &mut DamageFlash,
+        Option<&mut Handle<StandardMaterial>>,
+    )>,
+) {
+    for (entity, archetype, mut flash, material) in flashing.iter_mut() {
+        if !flash.0.tick(time.delta()).finished() {
+            continue;
+        }
+
+        commands.entity(entity).remove::<DamageFlash>();
+        if let (Some(mut material), Some(assets)) = (material, cache.assets.get(archetype)) {
+            *material = assets.material.clone();
+        }
+    }
+}
+
+/// Gather every instanced unit's transform and colour into its archetype's batch.
+pub fn update_instance_batches(
+    mut commands: Commands,
+    mut cache: ResMut<UnitRenderCache>,
+    units: Query<
+        (
+            &UnitArchetype,
+            &GlobalTransform,
+            &ComputedVisibility,
+            Option<&DamageFlash>,
+        ),
+        With<InstancedUnit>,
+    >,
+    mut batches: Query<&mut InstanceMaterialData, With<InstanceBatch>>,
+) {
+    let mut instances: HashMap<UnitArchetype, Vec<InstanceData>> = HashMap::default();
+    for (archetype, transform, visibility, flash) in units.iter() {
+        if !visibility.is_visible_in_hierarchy() {
+            continue;
+        }
+
+        let (scale, rotation, position) = transform.to_scale_rotation_translation();
+        let color = if flash.is_some() {
+            DAMAGE_FLASH_COLOR
+        } else {
+            archetype.color()
+        };
+        instances.entry(*archetype).or_default().push(InstanceData {
+            position,
+            scale: scale.x,
+            rotation: rotation.to_array(),
+            color: color.as_linear_rgba_f32(),
+        });
+    }
+
+    // Every batch gets updated, even to nothing, otherwise the last few units never disappear
+    for (archetype, batch) in cache.batches.iter() {
+        if let Ok(mut data) = batches.get_mut(*batch) {
+            data.0 = instances.remove(archetype).unwrap_or_default();
+        }
+    }
+
+    // Anything left is the first time we've seen that archetype instanced
+    for (archetype, data) in instances {
+        let Some(assets) = cache.assets.get(&archetype) else { continue; };
+        let batch = commands
+            .spawn((
+                assets.mesh.clone(),
+                SpatialBundle::INHERITED_IDENTITY,
+                InstanceMaterialData(data),
+                InstanceBatch(archetype),
+                // The batch sits at the origin but its instances are everywhere, so culling it
+                // by the mesh's bounds would hide them all the moment the origin is off screen.
+                NoFrustumCulling,
+                Name::new(format!("{archetype:?} instances")),
+            ))
+            .id();
+        cache.batches.insert(archetype, batch);
+    }
+}
@@ -0,0 +1,28 @@
+use bevy::prelude::*;
+
+#[derive(Component)]
+pub struct WalkToLocation(pub Vec3);
+
+#[derive(Component)]
+pub struct Player;
+
+#[derive(Component)]
+pub struct Enemy;
+
+#[derive(Component)]
+pub struct UnitMovement {
+    pub turn_speed: f32,
+    pub move_speed: f32,
+}
+
+#[derive(Component)]
+pub struct UnitSize {
+    pub collider: f32,
+    pub model: f32,
+}
+
+#[derive(Component)]
+pub struct UnitView {
+    pub fov: i16,
+    pub distance: f32,
+}
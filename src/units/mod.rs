@@ -0,0 +1,21 @@
+use bevy::prelude::*;
+
+pub mod components;
+pub mod spawn;
+mod systems;
+
+use crate::simulation::SimulationSet;
+use systems::*;
+
+/// Units walking to wherever they've been told to go.
+pub struct UnitsPlugin;
+
+impl Plugin for UnitsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            move_to_location
+                .in_set(SimulationSet::Movement)
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
+    }
+}
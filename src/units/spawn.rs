@@ -0,0 +1,138 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::{
+    ai::{
+        components::{BehaviourTreeRunner, Blackboard},
+        tree::BehaviourTree,
+    },
+    combat::components::{Health, UnitAttack},
+    save::components::UnitId,
+    selection::components::Selectable,
+    simulation::components::InterpolatedTransform,
+    unit_render::{
+        components::{InstancedUnit, UnitArchetype},
+        resources::UnitRenderCache,
+    },
+};
+
+use super::components::*;
+
+/// Spawn a unit of the given archetype with its stats, used for the initial world and when
+/// loading a save. Enemies get the behaviour tree, the player's units don't think for themselves.
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_unit(
+    commands: &mut Commands,
+    render_cache: &mut UnitRenderCache,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+    id: UnitId,
+    name: String,
+    archetype: UnitArchetype,
+    instanced: bool,
+    location: Vec3,
+    rotation: f32,
+    enemy_behaviour: &Handle<BehaviourTree>,
+) -> Entity {
+    let entity = create_cube(
+        commands,
+        render_cache,
+        meshes,
+        materials,
+        archetype,
+        instanced,
+        0.5,
+        location,
+        rotation,
+    );
+
+    let mut unit = commands.entity(entity);
+    unit.insert(id)
+        .insert(Name::new(name))
+        .insert(UnitMovement {
+            turn_speed: 0.5,
+            move_speed: 5.0,
+        })
+        .insert(UnitSize {
+            collider: 0.502,
+            model: 0.5,
+        });
+
+    match archetype {
+        UnitArchetype::Enemy => {
+            unit.insert(Enemy)
+                .insert(UnitView {
+                    fov: 90,
+                    distance: 5.0,
+                })
+                .insert(Health::new(50.0))
+                .insert(UnitAttack {
+                    range: 1.0,
+                    damage: 5.0,
+                    cooldown: 1.0,
+                })
+                .insert(BehaviourTreeRunner::new(enemy_behaviour.clone()))
+                .insert(Blackboard::default());
+        }
+        UnitArchetype::Player => {
+            unit.insert(Player)
+                .insert(UnitView {
+                    fov: 120,
+                    distance: 10.0,
+                })
+                .insert(Health::new(100.0))
+                .insert(UnitAttack {
+                    range: 1.5,
+                    damage: 10.0,
+                    cooldown: 0.5,
+                });
+        }
+    }
+
+    entity
+}
+
+/// Spawn a unit cube sharing its archetype's mesh and material, or drawn as part of the
+/// archetype's instance batch if `instanced`.
+#[allow(clippy::too_many_arguments)]
+pub fn create_cube(
+    commands: &mut Commands,
+    render_cache: &mut UnitRenderCache,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+    archetype: UnitArchetype,
+    instanced: bool,
+    size: f32,
+    location: Vec3,
+    rotation: f32,
+) -> Entity {
+    let collider_size = (size / 2.0) + 0.0001;
+    let offset_location = location + Vec3::Y * (size / 2.0);
+    let transform =
+        Transform::from_translation(offset_location).with_rotation(Quat::from_rotation_y(rotation));
+    let render_assets = render_cache.get_or_create(archetype, size, meshes, materials);
+
+    let mut unit = commands.spawn(RigidBody::Fixed);
+    if instanced {
+        unit.insert(SpatialBundle::default()).insert(InstancedUnit);
+    } else {
+        unit.insert(PbrBundle {
+            mesh: render_assets.mesh,
+            material: render_assets.material,
+            ..default()
+        });
+    }
+
+    unit.insert(archetype)
+        .insert(Collider::cuboid(
+            collider_size,
+            collider_size,
+            collider_size,
+        ))
+        .insert(ColliderMassProperties::Density(2.0))
+        .insert(Restitution::coefficient(0.7))
+        .insert(TransformBundle::from(transform))
+        .insert(InterpolatedTransform::from(transform))
+        .insert(Selectable)
+        .id()
+}
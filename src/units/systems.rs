@@ -0,0 +1,23 @@
+use bevy::prelude::*;
+
+use crate::simulation::resources::SimulationClock;
+
+use super::components::*;
+
+pub fn move_to_location(
+    mut commands: Commands,
+    clock: Res<SimulationClock>,
+    mut player_with_move: Query<(Entity, &mut Transform, &WalkToLocation)>,
+) {
+    let speed = 2.0;
+    let dt = clock.delta_seconds();
+    for (entity, mut transform, target) in player_with_move.iter_mut() {
+        let direction = target.0 - transform.translation;
+
+        transform.translation += direction * speed * dt;
+
+        if transform.translation.distance(target.0) <= 0.001 {
+            commands.entity(entity).remove::<WalkToLocation>();
+        }
+    }
+}
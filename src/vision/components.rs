@@ -0,0 +1,5 @@
+use bevy::prelude::*;
+
+/// An enemy one of the player's units can see right now, kept up to date every tick.
+#[derive(Component)]
+pub struct VisibleToPlayer;
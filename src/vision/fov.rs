@@ -0,0 +1,56 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+/// Cast a fan of rays `sensitivity` degrees apart across the unit's field of view, one result per
+/// ray with whatever it hit first. The viewer's own collider is skipped, it can lag a tick behind
+/// while the unit is moving.
+pub fn scan_fov(
+    rapier_context: &RapierContext,
+    viewer: Entity,
+    unit_transform: &Transform,
+    fov: i16,
+    origin: Vec3,
+    sensitivity: usize,
+    distance: f32,
+) -> Vec<FovScanResult> {
+    let mut results: Vec<FovScanResult> = Vec::new();
+
+    let filter = QueryFilter::default().exclude_collider(viewer);
+    let solid = true;
+    let half_fov = fov / 2;
+    let (yaw, _, _) = unit_transform.rotation.to_euler(EulerRot::YXZ);
+
+    for angle in (-half_fov..half_fov).step_by(sensitivity) {
+        let direction = direction_from_angle(angle, yaw);
+        if let Some((entity, toi)) =
+            rapier_context.cast_ray(origin, direction, distance, solid, filter)
+        {
+            results.push(FovScanResult {
+                angle,
+                hit_location: direction * toi,
+                entity: Some(entity),
+            });
+        } else {
+            results.push(FovScanResult {
+                angle,
+                hit_location: direction * distance,
+                entity: None,
+            });
+        }
+    }
+
+    results
+}
+
+#[derive(Debug, Default)]
+pub struct FovScanResult {
+    pub angle: i16,
+    pub hit_location: Vec3,
+    pub entity: Option<Entity>,
+}
+
+pub fn direction_from_angle(angle: impl Into<f32> + Copy, yaw_radians: f32) -> Vec3 {
+    let x: f32 = angle.into().to_radians() + yaw_radians;
+    let z: f32 = angle.into().to_radians() + yaw_radians;
+    -Vec3::new(x.sin(), 0.0, z.cos())
+}
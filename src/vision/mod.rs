@@ -0,0 +1,24 @@
+use bevy::prelude::*;
+
+pub mod components;
+pub mod fov;
+mod systems;
+
+use crate::simulation::SimulationSet;
+use systems::*;
+
+/// Draws what each enemy can see and marks the enemies the player's units can see (for the
+/// minimap's fog of war). The scanning itself is `fov::scan_fov` for anything that needs to
+/// look around.
+pub struct VisionPlugin;
+
+impl Plugin for VisionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(draw_view_gizmos).add_system(
+            // Once physics has caught up with where everything moved to this tick
+            update_visible_to_player
+                .after(SimulationSet::Store)
+                .in_schedule(CoreSchedule::FixedUpdate),
+        );
+    }
+}
@@ -0,0 +1,78 @@
+use bevy::{prelude::*, utils::HashSet};
+use bevy_mod_gizmos::prelude::Gizmos;
+use bevy_rapier3d::prelude::*;
+
+use crate::units::components::{Enemy, Player, UnitSize, UnitView};
+
+use super::{components::*, fov::scan_fov};
+
+/// Degrees between each ray of a unit's view.
+const SENSITIVITY: usize = 10;
+
+/// Where a unit looks from, the middle of its front face so it doesn't see itself.
+fn eye(transform: &Transform, size: &UnitSize) -> Vec3 {
+    transform.translation + (transform.forward() * size.collider / 2.0)
+}
+
+pub fn draw_view_gizmos(
+    units: Query<(Entity, &Transform, &UnitSize, &UnitView), With<Enemy>>,
+    mut gizmos: Gizmos,
+    rapier_context: Res<RapierContext>,
+) {
+    for (entity, unit_transform, unit_size, unit_view) in units.iter() {
+        let origin = eye(unit_transform, unit_size);
+
+        let view_results = scan_fov(
+            &rapier_context,
+            entity,
+            unit_transform,
+            unit_view.fov,
+            origin,
+            SENSITIVITY,
+            unit_view.distance,
+        );
+        // Green where it hit something, red where it could see all the way
+        for result in view_results.iter() {
+            let color = if result.entity.is_some() {
+                Color::GREEN
+            } else {
+                Color::RED
+            };
+            gizmos.ray(origin, result.hit_location, color);
+        }
+    }
+}
+
+/// Mark the enemies any of the player's units can see this tick, and unmark the ones none can.
+pub fn update_visible_to_player(
+    mut commands: Commands,
+    rapier_context: Res<RapierContext>,
+    viewers: Query<(Entity, &Transform, &UnitSize, &UnitView), With<Player>>,
+    enemies: Query<(Entity, Option<&VisibleToPlayer>), With<Enemy>>,
+) {
+    let mut seen = HashSet::new();
+    for (viewer, transform, size, view) in viewers.iter() {
+        let results = scan_fov(
+            &rapier_context,
+            viewer,
+            transform,
+            view.fov,
+            eye(transform, size),
+            SENSITIVITY,
+            view.distance,
+        );
+        seen.extend(results.iter().filter_map(|result| result.entity));
+    }
+
+    for (enemy, visible) in enemies.iter() {
+        match (seen.contains(&enemy), visible.is_some()) {
+            (true, false) => {
+                commands.entity(enemy).insert(VisibleToPlayer);
+            }
+            (false, true) => {
+                commands.entity(enemy).remove::<VisibleToPlayer>();
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,5 @@
+use bevy::prelude::*;
+
+/// The scenario's light, so it can go with the rest of the world.
+#[derive(Component)]
+pub struct Sun;
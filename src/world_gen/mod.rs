@@ -0,0 +1,26 @@
+use bevy::prelude::*;
+
+pub mod components;
+pub mod resources;
+mod systems;
+
+use crate::game_state::GameState;
+use resources::*;
+use systems::*;
+
+pub use systems::start_scenario;
+
+/// The ground, the light and the scenario's units. Spawned on the way into a game and gone again
+/// back at the main menu, the units respawn from the seed on `StartScenario`.
+pub struct WorldGenPlugin;
+
+impl Plugin for WorldGenPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Ground>()
+            .init_resource::<ActiveScenario>()
+            .add_event::<StartScenario>()
+            .add_systems((spawn_ground, spawn_world).in_schedule(OnEnter(GameState::Loading)))
+            .add_system(despawn_world.in_schedule(OnEnter(GameState::MainMenu)))
+            .add_system(start_scenario.run_if(on_event::<StartScenario>()));
+    }
+}
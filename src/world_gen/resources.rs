@@ -0,0 +1,85 @@
+use bevy::prelude::*;
+
+#[derive(Resource)]
+pub struct Ground {
+    pub size: i32,
+    pub subdivisions: u32,
+    pub mesh: Handle<Mesh>,
+    pub material: Handle<StandardMaterial>,
+    pub entity: Option<Entity>,
+}
+
+impl Default for Ground {
+    fn default() -> Self {
+        Ground {
+            size: 100,
+            subdivisions: 1,
+            mesh: Handle::default(),
+            material: Handle::default(),
+            entity: None,
+        }
+    }
+}
+
+/// Start the active scenario over from its seed.
+pub struct StartScenario;
+
+/// A world to play, picked from the main menu or with `--scenario`.
+pub struct ScenarioDefinition {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub ground_size: i32,
+    pub enemies: usize,
+}
+
+/// Everything the main menu offers, the first is the default.
+pub const SCENARIOS: &[ScenarioDefinition] = &[
+    ScenarioDefinition {
+        name: "skirmish",
+        description: "A thousand enemies on open ground",
+        ground_size: 100,
+        enemies: 1000,
+    },
+    ScenarioDefinition {
+        name: "outpost",
+        description: "A small patrol on a small map",
+        ground_size: 50,
+        enemies: 150,
+    },
+    ScenarioDefinition {
+        name: "swarm",
+        description: "Far more enemies than is fair",
+        ground_size: 160,
+        enemies: 2500,
+    },
+];
+
+/// The scenario being played, anything saved per scenario (camera bookmarks etc.) goes in its save folder.
+#[derive(Resource)]
+pub struct ActiveScenario {
+    pub name: String,
+}
+
+impl Default for ActiveScenario {
+    fn default() -> Self {
+        ActiveScenario {
+            name: SCENARIOS[0].name.to_string(),
+        }
+    }
+}
+
+impl ActiveScenario {
+    /// The command line only lets through names we know, anything else plays like the default.
+    pub fn definition(&self) -> &'static ScenarioDefinition {
+        SCENARIOS
+            .iter()
+            .find(|scenario| scenario.name == self.name)
+            .unwrap_or(&SCENARIOS[0])
+    }
+
+    pub fn save_dir(&self) -> std::path::PathBuf {
+        bevy::asset::FileAssetIo::get_base_path()
+            .join("saves")
+            .join(&self.name)
+    }
+}
@@ -0,0 +1,184 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use bevy_turborand::prelude::*;
+
+use crate::{
+    ai::ENEMY_BEHAVIOUR_PATH,
+    save::{
+        components::UnitId,
+        resources::{GameSeed, UnitIds},
+    },
+    selection::resources::Hovered,
+    simulation::resources::SimulationTick,
+    unit_render::{
+        components::UnitArchetype,
+        resources::{UnitRenderCache, UnitRenderSettings},
+    },
+    units::spawn::spawn_unit,
+};
+
+use super::{components::*, resources::*};
+
+pub fn spawn_ground(
+    mut commands: Commands,
+    scenario: Res<ActiveScenario>,
+    mut ground: ResMut<Ground>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    ground.size = scenario.definition().ground_size;
+    let mesh = meshes.add(Mesh::from(shape::Plane {
+        size: ground.size as f32,
+        subdivisions: ground.subdivisions,
+    }));
+
+    let material = materials.add(Color::GREEN.into());
+
+    let ground_id = commands
+        .spawn(PbrBundle {
+            mesh: mesh.clone(),
+            material: material.clone(),
+            ..default()
+        })
+        .insert(Collider::cuboid(
+            (ground.size / 2) as f32,
+            0.1,
+            (ground.size / 2) as f32,
+        ))
+        .insert(RigidBody::Fixed)
+        .insert(TransformBundle::from(Transform::from_xyz(0.0, -0.1, 0.0)))
+        .insert(Name::new("Ground"))
+        .id();
+
+    ground.mesh = mesh;
+    ground.material = material;
+    ground.entity = Some(ground_id);
+
+    //TODO: Perform noise or some form of generation to make the ground actually interesting.
+}
+
+pub fn spawn_world(mut commands: Commands, mut scenario: EventWriter<StartScenario>) {
+    commands
+        .spawn(DirectionalLightBundle {
+            transform: Transform::from_xyz(50.0, 50.0, 50.0),
+            directional_light: DirectionalLight {
+                shadows_enabled: true,
+                illuminance: 10000.0,
+                ..default()
+            },
+            ..default()
+        })
+        .insert(Sun);
+
+    scenario.send(StartScenario);
+}
+
+/// Back to the main menu, nothing of the last game should be left for the next one.
+#[allow(clippy::type_complexity)]
+pub fn despawn_world(
+    mut commands: Commands,
+    mut ground: ResMut<Ground>,
+    mut unit_ids: ResMut<UnitIds>,
+    mut tick: ResMut<SimulationTick>,
+    mut hovered: ResMut<Hovered>,
+    world: Query<Entity, Or<(With<UnitId>, With<Sun>)>>,
+) {
+    for entity in world.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    *hovered = Hovered::default();
+    if let Some(entity) = ground.entity.take() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    *unit_ids = UnitIds::default();
+    tick.0 = 0;
+}
+
+/// Throw away any units and spawn the scenario's from the start, everything random comes from
+/// the seed so the same seed always gives the same world.
+#[allow(clippy::too_many_arguments)]
+pub fn start_scenario(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+    ground: Res<Ground>,
+    scenario: Res<ActiveScenario>,
+    mut render_cache: ResMut<UnitRenderCache>,
+    render_settings: Res<UnitRenderSettings>,
+    mut unit_ids: ResMut<UnitIds>,
+    seed: Res<GameSeed>,
+    mut rng: ResMut<GlobalRng>,
+    mut tick: ResMut<SimulationTick>,
+    mut hovered: ResMut<Hovered>,
+    existing: Query<Entity, With<UnitId>>,
+) {
+    for entity in existing.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    // The hover ring was a child of one of the units we just despawned
+    *hovered = Hovered::default();
+
+    *rng = GlobalRng::with_seed(seed.0);
+    *unit_ids = UnitIds::default();
+    tick.0 = 0;
+
+    let enemy_behaviour = asset_server.load(ENEMY_BEHAVIOUR_PATH);
+    let max = ground.size / 2 - 2;
+
+    let spawn_amount = scenario.definition().enemies;
+    let mut spawned: Vec<Vec3> = vec![];
+    let instance_enemies = spawn_amount >= render_settings.instancing_threshold;
+
+    for i in 0..spawn_amount {
+        let r = rng.i32(0..360) as f32;
+
+        let mut valid_new_location: Option<Vec3> = None;
+
+        for _ in 0..100 {
+            let test_new_location =
+                Vec3::new(rng.i32(-max..max) as f32, 0.0, rng.i32(-max..max) as f32);
+            if spawned
+                .iter()
+                .all(|exist| test_new_location.distance(*exist) > 0.5 * 3.0)
+            {
+                spawned.push(test_new_location);
+                valid_new_location = Some(test_new_location);
+                break;
+            };
+        }
+
+        if let Some(valid_new_location) = valid_new_location {
+            spawn_unit(
+                &mut commands,
+                &mut render_cache,
+                &mut meshes,
+                &mut materials,
+                unit_ids.allocate(),
+                format!("Enemy {i}"),
+                UnitArchetype::Enemy,
+                instance_enemies,
+                valid_new_location,
+                r,
+                &enemy_behaviour,
+            );
+        } else {
+            error!("No valid Location for enemy spawn found.");
+        }
+    }
+
+    spawn_unit(
+        &mut commands,
+        &mut render_cache,
+        &mut meshes,
+        &mut materials,
+        unit_ids.allocate(),
+        "Player".to_string(),
+        UnitArchetype::Player,
+        false,
+        Vec3::new(0.0, 0.0, 1.0),
+        0.0,
+        &enemy_behaviour,
+    );
+}
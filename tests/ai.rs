@@ -0,0 +1,55 @@
+mod common;
+
+use bevy::prelude::*;
+use rust_game::{
+    ai::components::{Blackboard, BlackboardValue},
+    combat::components::AttackTarget,
+    simulation::components::InterpolatedTransform,
+    unit_render::components::UnitArchetype,
+    units::components::WalkToLocation,
+};
+
+use common::TestApp;
+
+fn setup() -> (TestApp, Entity) {
+    let mut test = TestApp::with_ai();
+    test.spawn_ground(40);
+    let enemy = test.spawn_unit(UnitArchetype::Enemy, Vec3::ZERO);
+    (test, enemy)
+}
+
+#[test]
+fn wanders_with_nobody_around() {
+    let (mut test, enemy) = setup();
+
+    let wandering = test.step_until(100, |world| world.get::<WalkToLocation>(enemy).is_some());
+
+    assert!(wandering, "never started wandering");
+    assert!(test.get::<AttackTarget>(enemy).is_none());
+}
+
+#[test]
+fn attacks_a_hostile_that_turns_up_mid_wander() {
+    let (mut test, enemy) = setup();
+    let wandering = test.step_until(100, |world| world.get::<WalkToLocation>(enemy).is_some());
+    assert!(wandering, "never started wandering");
+
+    // Right where it's looking now, part way through walking somewhere
+    let transform = test.get::<InterpolatedTransform>(enemy).unwrap().current;
+    let hostile = test.spawn_unit(
+        UnitArchetype::Player,
+        transform.translation + transform.forward() * 3.0,
+    );
+
+    // Walking there and the wait after take far longer than this, so it has to have looked again
+    test.step_n(3);
+
+    assert_eq!(
+        test.get::<AttackTarget>(enemy).map(|target| target.0),
+        Some(hostile)
+    );
+    assert_eq!(
+        test.get::<Blackboard>(enemy).unwrap().get("target"),
+        Some(BlackboardValue::Entity(hostile))
+    );
+}
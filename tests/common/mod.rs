@@ -0,0 +1,292 @@
+// Builds a windowless app with the game's plugins and drives it with synthetic mouse and
+// keyboard input, a simulation tick per frame. Not every test file uses every helper.
+#![allow(dead_code, clippy::type_complexity)]
+
+use std::time::{Duration, Instant};
+
+use bevy::{
+    ecs::system::SystemState,
+    input::{
+        keyboard::KeyboardInput,
+        mouse::{MouseButtonInput, MouseMotion},
+        ButtonState, InputPlugin,
+    },
+    prelude::*,
+    render::camera::{camera_system, Projection},
+    time::TimeUpdateStrategy,
+    window::{CursorMoved, PrimaryWindow},
+};
+use bevy_polyline::prelude::{Polyline, PolylineMaterial};
+use bevy_rapier3d::prelude::*;
+use bevy_turborand::prelude::RngPlugin;
+
+use rust_game::{
+    ai::{
+        tree::{BehaviourNode, BehaviourTree},
+        AiPlugin, ENEMY_BEHAVIOUR_PATH,
+    },
+    camera::components::PlayerCamera,
+    game_state::GameState,
+    intent::IntentPlugin,
+    save::resources::{GameSeed, UnitIds},
+    selection::{components::SelectedUnit, SelectionPlugin},
+    simulation::{SimulationPlugin, SIMULATION_TICK},
+    unit_render::{components::UnitArchetype, resources::UnitRenderCache},
+    units::{spawn::spawn_unit, UnitsPlugin},
+    world_gen::resources::Ground,
+};
+
+pub const TEST_SEED: u64 = 1234;
+
+pub struct TestApp {
+    pub app: App,
+    /// What the app thinks the time is, a tick further on every frame however long it took.
+    clock: Instant,
+    /// The tree enemies get, nothing unless the app was made `with_ai`.
+    enemy_behaviour: Handle<BehaviourTree>,
+}
+
+impl TestApp {
+    /// Just the simulation, physics on the fixed tick and units walking to where they're told.
+    pub fn new() -> Self {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(AssetPlugin::default())
+            .add_plugin(WindowPlugin::default())
+            .add_plugin(TransformPlugin)
+            .add_plugin(HierarchyPlugin)
+            .add_plugin(InputPlugin)
+            // Units and selection rings still get meshes and materials, nothing draws them
+            .add_asset::<Mesh>()
+            .add_asset::<StandardMaterial>()
+            .add_asset::<Image>()
+            .add_asset::<Polyline>()
+            .add_asset::<PolylineMaterial>()
+            .init_resource::<UnitRenderCache>()
+            .init_resource::<UnitIds>()
+            .init_resource::<Ground>()
+            .insert_resource(GameSeed(TEST_SEED))
+            .add_plugin(
+                RapierPhysicsPlugin::<NoUserData>::default().with_default_system_setup(false),
+            )
+            .add_plugin(RngPlugin::new().with_rng_seed(TEST_SEED))
+            // The plugins clean up on going back to the menu so they need the states, but we
+            // skip the menu and go straight into the game
+            .add_state::<GameState>()
+            .insert_resource(State(GameState::InGame))
+            .add_plugin(SimulationPlugin)
+            .add_plugin(UnitsPlugin)
+            // Normally the renderer works out the camera's projection, we need it for the cursor
+            .add_system(camera_system::<Projection>.in_base_set(CoreSet::PostUpdate));
+
+        TestApp {
+            app,
+            clock: Instant::now(),
+            enemy_behaviour: Handle::default(),
+        }
+    }
+
+    /// Everything `new` has, plus clicking on and box selecting units.
+    pub fn with_selection() -> Self {
+        let mut test = TestApp::new();
+        test.app
+            .add_plugin(IntentPlugin)
+            .add_plugin(SelectionPlugin);
+        test
+    }
+
+    /// Everything `new` has, plus enemies running the game's behaviour tree. The tree's read
+    /// straight from the assets folder so tests don't have to wait for the asset server.
+    pub fn with_ai() -> Self {
+        let mut test = TestApp::new();
+        test.app.add_plugin(AiPlugin);
+
+        let path = format!("assets/{ENEMY_BEHAVIOUR_PATH}");
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let root: BehaviourNode = ron::from_str(&contents).unwrap();
+        test.enemy_behaviour = test
+            .app
+            .world
+            .resource_mut::<Assets<BehaviourTree>>()
+            .add(root.into());
+        test
+    }
+
+    /// Run one frame, which is exactly one simulation tick.
+    pub fn step(&mut self) {
+        self.clock += Duration::from_secs_f32(SIMULATION_TICK);
+        self.app
+            .world
+            .insert_resource(TimeUpdateStrategy::ManualInstant(self.clock));
+        self.app.update();
+    }
+
+    pub fn step_n(&mut self, frames: usize) {
+        for _ in 0..frames {
+            self.step();
+        }
+    }
+
+    /// Step until `done` says so, false if it still hasn't after `max_frames`.
+    pub fn step_until(&mut self, max_frames: usize, mut done: impl FnMut(&World) -> bool) -> bool {
+        for _ in 0..max_frames {
+            if done(&self.app.world) {
+                return true;
+            }
+            self.step();
+        }
+        done(&self.app.world)
+    }
+
+    /// A flat ground `size` across with its top at y = 0, same as the game's.
+    pub fn spawn_ground(&mut self, size: i32) -> Entity {
+        let half = size as f32 / 2.0;
+        let entity = self
+            .app
+            .world
+            .spawn(Collider::cuboid(half, 0.1, half))
+            .insert(RigidBody::Fixed)
+            .insert(TransformBundle::from(Transform::from_xyz(0.0, -0.1, 0.0)))
+            .insert(Name::new("Ground"))
+            .id();
+
+        let mut ground = self.app.world.resource_mut::<Ground>();
+        ground.size = size;
+        ground.entity = Some(entity);
+        entity
+    }
+
+    /// A unit standing on the ground at `location`, facing down -Z.
+    pub fn spawn_unit(&mut self, archetype: UnitArchetype, location: Vec3) -> Entity {
+        let mut state: SystemState<(
+            Commands,
+            ResMut<UnitRenderCache>,
+            ResMut<Assets<Mesh>>,
+            ResMut<Assets<StandardMaterial>>,
+            ResMut<UnitIds>,
+        )> = SystemState::new(&mut self.app.world);
+        let (mut commands, mut render_cache, mut meshes, mut materials, mut unit_ids) =
+            state.get_mut(&mut self.app.world);
+
+        let id = unit_ids.allocate();
+        let entity = spawn_unit(
+            &mut commands,
+            &mut render_cache,
+            &mut meshes,
+            &mut materials,
+            id,
+            format!("{archetype:?} {}", id.0),
+            archetype,
+            false,
+            location,
+            0.0,
+            &self.enemy_behaviour,
+        );
+        state.apply(&mut self.app.world);
+        entity
+    }
+
+    /// The player's camera, looking straight down on `focus` from `height` above it.
+    pub fn spawn_camera(&mut self, focus: Vec3, height: f32) -> Entity {
+        self.app
+            .world
+            .spawn(Camera3dBundle {
+                transform: Transform::from_translation(focus + Vec3::Y * height)
+                    .looking_at(focus, Vec3::NEG_Z),
+                ..default()
+            })
+            .insert(PlayerCamera)
+            .id()
+    }
+
+    /// Where a point in the world shows up on screen through the player camera.
+    pub fn world_to_screen(&mut self, position: Vec3) -> Option<Vec2> {
+        let mut cameras = self
+            .app
+            .world
+            .query_filtered::<(&Camera, &GlobalTransform), With<PlayerCamera>>();
+        let (camera, transform) = cameras.single(&self.app.world);
+        camera.world_to_viewport(transform, position)
+    }
+
+    /// Move the cursor to a spot on screen, in the same coordinates `Window::cursor_position` uses.
+    pub fn move_cursor(&mut self, position: Vec2) {
+        let mut windows = self
+            .app
+            .world
+            .query_filtered::<(Entity, &mut Window), With<PrimaryWindow>>();
+        let (window, mut window_state) = windows.single_mut(&mut self.app.world);
+        let previous = window_state.cursor_position().unwrap_or(position);
+        window_state.set_cursor_position(Some(position));
+
+        self.app.world.send_event(CursorMoved { window, position });
+        self.app.world.send_event(MouseMotion {
+            delta: position - previous,
+        });
+    }
+
+    /// Put the cursor over a point in the world.
+    pub fn hover(&mut self, position: Vec3) {
+        let Some(screen) = self.world_to_screen(position) else {
+            panic!("{position} isn't on screen");
+        };
+        self.move_cursor(screen);
+    }
+
+    pub fn press_mouse(&mut self, button: MouseButton) {
+        self.app.world.send_event(MouseButtonInput {
+            button,
+            state: ButtonState::Pressed,
+        });
+    }
+
+    pub fn release_mouse(&mut self, button: MouseButton) {
+        self.app.world.send_event(MouseButtonInput {
+            button,
+            state: ButtonState::Released,
+        });
+    }
+
+    pub fn press_key(&mut self, key: KeyCode) {
+        self.app.world.send_event(KeyboardInput {
+            scan_code: 0,
+            key_code: Some(key),
+            state: ButtonState::Pressed,
+        });
+    }
+
+    pub fn release_key(&mut self, key: KeyCode) {
+        self.app.world.send_event(KeyboardInput {
+            scan_code: 0,
+            key_code: Some(key),
+            state: ButtonState::Released,
+        });
+    }
+
+    /// Drag a box with the left mouse between two points on the ground, a frame for each part.
+    pub fn drag_select(&mut self, from: Vec3, to: Vec3) {
+        self.hover(from);
+        self.press_mouse(MouseButton::Left);
+        self.step();
+        self.hover(to);
+        self.step();
+        self.release_mouse(MouseButton::Left);
+        self.step();
+        // What's in the box is pending until the frame after
+        self.step();
+    }
+
+    pub fn get<T: Component>(&self, entity: Entity) -> Option<&T> {
+        self.app.world.get::<T>(entity)
+    }
+
+    pub fn selected(&mut self) -> Vec<Entity> {
+        let mut selected = self
+            .app
+            .world
+            .query_filtered::<Entity, With<SelectedUnit>>();
+        let mut entities: Vec<Entity> = selected.iter(&self.app.world).collect();
+        entities.sort();
+        entities
+    }
+}
@@ -0,0 +1,146 @@
+mod common;
+
+use bevy::prelude::*;
+use rust_game::{
+    orders::{components::Patrol, OrderSimulationPlugin},
+    replay::resources::TickCommands,
+    simulation::components::InterpolatedTransform,
+    unit_render::components::UnitArchetype,
+    units::components::WalkToLocation,
+};
+
+use common::TestApp;
+
+/// Where the simulation has the unit, rather than where it's drawn part way between ticks.
+fn simulated_position(test: &TestApp, unit: Entity) -> Vec3 {
+    test.get::<InterpolatedTransform>(unit)
+        .expect("unit should have an interpolated transform")
+        .current
+        .translation
+}
+
+fn setup() -> (TestApp, Entity) {
+    let mut test = TestApp::new();
+    test.spawn_ground(40);
+    let unit = test.spawn_unit(UnitArchetype::Player, Vec3::ZERO);
+    test.step_n(2);
+    (test, unit)
+}
+
+#[test]
+fn walks_to_the_location_and_stops() {
+    let (mut test, unit) = setup();
+    let start = simulated_position(&test, unit);
+    let target = start + Vec3::new(4.0, 0.0, -3.0);
+    test.app
+        .world
+        .entity_mut(unit)
+        .insert(WalkToLocation(target));
+
+    let arrived = test.step_until(400, |world| world.get::<WalkToLocation>(unit).is_none());
+
+    assert!(arrived, "never got there");
+    assert!(simulated_position(&test, unit).distance(target) <= 0.001);
+}
+
+#[test]
+fn gets_closer_every_tick() {
+    let (mut test, unit) = setup();
+    let target = simulated_position(&test, unit) + Vec3::new(-6.0, 0.0, 2.0);
+    test.app
+        .world
+        .entity_mut(unit)
+        .insert(WalkToLocation(target));
+
+    let mut distance = simulated_position(&test, unit).distance(target);
+    for _ in 0..10 {
+        test.step();
+        let now = simulated_position(&test, unit).distance(target);
+        assert!(now < distance, "went from {distance} to {now}");
+        distance = now;
+    }
+    assert!(test.get::<WalkToLocation>(unit).is_some());
+}
+
+#[test]
+fn stays_put_without_an_order() {
+    let (mut test, unit) = setup();
+    let start = simulated_position(&test, unit);
+
+    test.step_n(20);
+
+    assert_eq!(simulated_position(&test, unit), start);
+}
+
+#[test]
+fn same_walk_takes_the_same_ticks_every_time() {
+    let ticks_to_arrive = || {
+        let (mut test, unit) = setup();
+        let target = simulated_position(&test, unit) + Vec3::new(3.0, 0.0, 3.0);
+        test.app
+            .world
+            .entity_mut(unit)
+            .insert(WalkToLocation(target));
+
+        let mut ticks = 0;
+        while test.get::<WalkToLocation>(unit).is_some() && ticks < 400 {
+            test.step();
+            ticks += 1;
+        }
+        (ticks, simulated_position(&test, unit))
+    };
+
+    assert_eq!(ticks_to_arrive(), ticks_to_arrive());
+}
+
+#[test]
+fn patrol_carries_on_after_being_stopped_part_way() {
+    let (mut test, unit) = setup();
+    test.app
+        .add_plugin(OrderSimulationPlugin)
+        .init_resource::<TickCommands>();
+    let from = simulated_position(&test, unit);
+    let to = from + Vec3::new(5.0, 0.0, 0.0);
+    test.app
+        .world
+        .entity_mut(unit)
+        .insert(Patrol { from, to })
+        .insert(WalkToLocation(to));
+    test.step_n(10);
+
+    // Same as a fight ending, the walk's gone but it's nowhere near the end yet
+    test.app.world.entity_mut(unit).remove::<WalkToLocation>();
+    test.step();
+
+    assert_eq!(
+        test.get::<WalkToLocation>(unit).map(|walk| walk.0),
+        Some(to)
+    );
+    assert_eq!(test.get::<Patrol>(unit).map(|patrol| patrol.to), Some(to));
+}
+
+#[test]
+fn patrol_turns_around_at_the_end() {
+    let (mut test, unit) = setup();
+    test.app
+        .add_plugin(OrderSimulationPlugin)
+        .init_resource::<TickCommands>();
+    let from = simulated_position(&test, unit);
+    let to = from + Vec3::new(0.0, 0.0, 3.0);
+    test.app
+        .world
+        .entity_mut(unit)
+        .insert(Patrol { from, to })
+        .insert(WalkToLocation(to));
+
+    let turned = test.step_until(400, |world| {
+        world.get::<Patrol>(unit).map(|patrol| patrol.to) == Some(from)
+    });
+
+    assert!(turned, "never turned around");
+    assert!(simulated_position(&test, unit).distance(to) <= 0.01);
+    assert_eq!(
+        test.get::<WalkToLocation>(unit).map(|walk| walk.0),
+        Some(from)
+    );
+}
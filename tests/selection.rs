@@ -0,0 +1,98 @@
+mod common;
+
+use bevy::prelude::*;
+use rust_game::{selection::components::SelectedUnit, unit_render::components::UnitArchetype};
+
+use common::TestApp;
+
+/// Three units on a ground under a camera looking straight down, two close together at the front
+/// left and one off on its own at the back right.
+fn setup() -> (TestApp, [Entity; 3]) {
+    let mut test = TestApp::with_selection();
+    test.spawn_ground(40);
+    test.spawn_camera(Vec3::ZERO, 30.0);
+    let units = [
+        test.spawn_unit(UnitArchetype::Player, Vec3::new(-4.0, 0.0, -4.0)),
+        test.spawn_unit(UnitArchetype::Player, Vec3::new(-2.0, 0.0, -3.0)),
+        test.spawn_unit(UnitArchetype::Player, Vec3::new(5.0, 0.0, 5.0)),
+    ];
+    // Colliders into the physics world and the camera's projection worked out
+    test.step_n(3);
+    (test, units)
+}
+
+#[test]
+fn box_selects_the_units_inside_it() {
+    let (mut test, [a, b, c]) = setup();
+
+    test.drag_select(Vec3::new(-6.0, 0.0, -6.0), Vec3::new(0.0, 0.0, 0.0));
+
+    let selected = test.selected();
+    assert!(selected.contains(&a));
+    assert!(selected.contains(&b));
+    assert!(!selected.contains(&c));
+}
+
+#[test]
+fn box_selects_dragging_in_any_direction() {
+    let (mut test, [_, _, c]) = setup();
+
+    test.drag_select(Vec3::new(8.0, 0.0, 8.0), Vec3::new(3.0, 0.0, 3.0));
+
+    assert_eq!(test.selected(), vec![c]);
+}
+
+#[test]
+fn empty_box_clears_the_selection() {
+    let (mut test, _) = setup();
+
+    test.drag_select(Vec3::new(-6.0, 0.0, -6.0), Vec3::new(0.0, 0.0, 0.0));
+    assert_eq!(test.selected().len(), 2);
+
+    test.drag_select(Vec3::new(10.0, 0.0, -10.0), Vec3::new(12.0, 0.0, -8.0));
+    assert!(test.selected().is_empty());
+}
+
+#[test]
+fn new_box_replaces_the_selection() {
+    let (mut test, [a, b, c]) = setup();
+
+    test.drag_select(Vec3::new(-6.0, 0.0, -6.0), Vec3::new(0.0, 0.0, 0.0));
+    test.drag_select(Vec3::new(8.0, 0.0, 8.0), Vec3::new(3.0, 0.0, 3.0));
+
+    assert_eq!(test.selected(), vec![c]);
+    assert!(test.get::<SelectedUnit>(a).is_none());
+    assert!(test.get::<SelectedUnit>(b).is_none());
+}
+
+#[test]
+fn shift_adds_to_the_selection() {
+    let (mut test, [a, b, c]) = setup();
+
+    test.drag_select(Vec3::new(-6.0, 0.0, -6.0), Vec3::new(0.0, 0.0, 0.0));
+
+    test.press_key(KeyCode::LShift);
+    test.drag_select(Vec3::new(8.0, 0.0, 8.0), Vec3::new(3.0, 0.0, 3.0));
+    test.release_key(KeyCode::LShift);
+    test.step();
+
+    let mut expected = vec![a, b, c];
+    expected.sort();
+    assert_eq!(test.selected(), expected);
+}
+
+#[test]
+fn shift_selecting_an_already_selected_unit_keeps_one_ring() {
+    let (mut test, [a, _, _]) = setup();
+
+    test.drag_select(Vec3::new(-6.0, 0.0, -6.0), Vec3::new(-3.0, 0.0, -3.0));
+    let ring = test.get::<SelectedUnit>(a).expect("a should be selected").0;
+
+    test.press_key(KeyCode::RShift);
+    test.drag_select(Vec3::new(-6.0, 0.0, -6.0), Vec3::new(-3.0, 0.0, -3.0));
+    test.release_key(KeyCode::RShift);
+
+    let selected = test.get::<SelectedUnit>(a);
+    assert_eq!(selected.map(|selected| selected.0), Some(ring));
+    assert!(test.app.world.get_entity(ring).is_some());
+}
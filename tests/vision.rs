@@ -0,0 +1,137 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::RapierContext;
+use rust_game::{
+    simulation::components::InterpolatedTransform,
+    unit_render::components::UnitArchetype,
+    units::components::{UnitSize, UnitView},
+    vision::fov::{scan_fov, FovScanResult},
+};
+
+use common::TestApp;
+
+const SENSITIVITY: usize = 10;
+
+/// Scan from `viewer` the same way the AI does, from the front face of the unit.
+fn scan(test: &TestApp, viewer: Entity) -> Vec<FovScanResult> {
+    let transform = *test.get::<Transform>(viewer).unwrap();
+    let size = test.get::<UnitSize>(viewer).unwrap();
+    let view = test.get::<UnitView>(viewer).unwrap();
+    let origin = transform.translation + transform.forward() * size.collider / 2.0;
+
+    scan_fov(
+        test.app.world.resource::<RapierContext>(),
+        viewer,
+        &transform,
+        view.fov,
+        origin,
+        SENSITIVITY,
+        view.distance,
+    )
+}
+
+fn seen(results: &[FovScanResult]) -> Vec<Entity> {
+    results.iter().filter_map(|result| result.entity).collect()
+}
+
+/// A player unit at the origin looking down -Z, it sees 120 degrees out to 10.
+fn setup() -> (TestApp, Entity) {
+    let mut test = TestApp::new();
+    test.spawn_ground(40);
+    let viewer = test.spawn_unit(UnitArchetype::Player, Vec3::ZERO);
+    (test, viewer)
+}
+
+#[test]
+fn casts_a_ray_every_few_degrees_across_the_fov() {
+    let (mut test, viewer) = setup();
+    test.step_n(2);
+
+    let results = scan(&test, viewer);
+    let fov = test.get::<UnitView>(viewer).unwrap().fov;
+
+    assert_eq!(results.len(), fov as usize / SENSITIVITY);
+    assert_eq!(results.first().map(|result| result.angle), Some(-fov / 2));
+    assert!(results
+        .windows(2)
+        .all(|pair| pair[1].angle - pair[0].angle == SENSITIVITY as i16));
+}
+
+#[test]
+fn misses_go_the_full_distance() {
+    let (mut test, viewer) = setup();
+    test.step_n(2);
+
+    let distance = test.get::<UnitView>(viewer).unwrap().distance;
+    let results = scan(&test, viewer);
+
+    assert!(seen(&results).is_empty());
+    for result in results {
+        assert!((result.hit_location.length() - distance).abs() < 0.001);
+        // Flat along the ground, not into it
+        assert!(result.hit_location.y.abs() < 0.001);
+    }
+}
+
+#[test]
+fn sees_a_unit_in_front() {
+    let (mut test, viewer) = setup();
+    let target = test.spawn_unit(UnitArchetype::Enemy, Vec3::new(0.0, 0.0, -4.0));
+    test.step_n(2);
+
+    let results = scan(&test, viewer);
+    let hit = results
+        .iter()
+        .find(|result| result.entity == Some(target))
+        .expect("should see the unit in front");
+
+    assert_eq!(hit.angle, 0);
+    // Hits the near face, a unit is half a unit wide
+    assert!((hit.hit_location.length() - (4.0 - 0.25 - 0.251)).abs() < 0.01);
+}
+
+#[test]
+fn does_not_see_behind_or_past_its_view_distance() {
+    let (mut test, viewer) = setup();
+    let behind = test.spawn_unit(UnitArchetype::Enemy, Vec3::new(0.0, 0.0, 4.0));
+    let too_far = test.spawn_unit(UnitArchetype::Enemy, Vec3::new(0.0, 0.0, -15.0));
+    test.step_n(2);
+
+    let seen = seen(&scan(&test, viewer));
+
+    assert!(!seen.contains(&behind));
+    assert!(!seen.contains(&too_far));
+}
+
+#[test]
+fn units_block_the_view_of_units_behind_them() {
+    let (mut test, viewer) = setup();
+    let blocker = test.spawn_unit(UnitArchetype::Enemy, Vec3::new(0.0, 0.0, -2.0));
+    let hidden = test.spawn_unit(UnitArchetype::Enemy, Vec3::new(0.0, 0.0, -5.0));
+    test.step_n(2);
+
+    let seen = seen(&scan(&test, viewer));
+
+    assert!(seen.contains(&blocker));
+    assert!(!seen.contains(&hidden));
+}
+
+#[test]
+fn turning_changes_what_it_sees() {
+    let (mut test, viewer) = setup();
+    let right = test.spawn_unit(UnitArchetype::Enemy, Vec3::new(4.0, 0.0, 0.0));
+    test.step_n(2);
+    assert!(!seen(&scan(&test, viewer)).contains(&right));
+
+    // A quarter turn clockwise from looking down -Z is looking down +X
+    let mut interpolated = test
+        .app
+        .world
+        .get_mut::<InterpolatedTransform>(viewer)
+        .unwrap();
+    interpolated.current.rotate_y(-std::f32::consts::FRAC_PI_2);
+    test.step_n(2);
+
+    assert!(seen(&scan(&test, viewer)).contains(&right));
+}